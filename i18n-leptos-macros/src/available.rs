@@ -0,0 +1,173 @@
+use crate::ftl;
+use proc_macro::TokenStream;
+use quote::quote;
+use std::path::Path;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{LitStr, Token};
+
+struct AvailableLocalesMacroInput {
+    path: LitStr,
+    display_name: Option<LitStr>,
+}
+
+impl Parse for AvailableLocalesMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path: LitStr = input.parse()?;
+        let mut display_name = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: LitStr = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.value().as_str() {
+                "display_name" => display_name = Some(input.parse()?),
+                _ => return Err(syn::Error::new_spanned(key, "Expected 'display_name'")),
+            }
+        }
+
+        Ok(AvailableLocalesMacroInput { path, display_name })
+    }
+}
+
+/// A locale directory found in the locales directory.
+struct LocaleDir {
+    langid: String,
+    /// The value of the display name message and the file it was read from, if found.
+    display_name: Option<(String, String)>,
+    /// The FTL files of the locale.
+    files: Vec<String>,
+}
+
+/// Parses the name of a locale directory, e.g. `en-US` or `sr_Latn`, into the canonical form
+/// of its language identifier, so that the generated code can parse it back infallibly.
+fn parse_langid(name: &str) -> std::result::Result<String, String> {
+    name.parse::<unic_langid::LanguageIdentifier>()
+        .map(|langid| langid.to_string())
+        .map_err(|err| {
+            format!("'{name}' in the locales directory isn't a language identifier: {err}")
+        })
+}
+
+/// Returns the value of the message `id` in the `.ftl` files directly in `dir`, along with
+/// the file it was found in. Only single-line values without placeables are supported.
+fn find_display_name(
+    dir: &Path,
+    id: &str,
+) -> std::result::Result<Option<(String, String)>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| format!("failed to read locale directory '{}': {err}", dir.display()))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read FTL file '{}': {err}", path.display()))?;
+        let value = source.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim_end() == id && !line.starts_with(char::is_whitespace))
+                .then(|| value.trim().to_string())
+        });
+        if let Some(value) = value {
+            return Ok(Some((value, path.to_string_lossy().into_owned())));
+        }
+    }
+    Ok(None)
+}
+
+pub fn available_locales_impl(input: TokenStream) -> TokenStream {
+    let AvailableLocalesMacroInput { path, display_name } = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let dir = ftl::resolve_path(&path.value());
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            let err = format!(
+                "failed to read locales directory '{}': {err}",
+                dir.display()
+            );
+            return syn::Error::new_spanned(path, err).to_compile_error().into();
+        }
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    let mut locales = Vec::new();
+    for locale_dir in dirs {
+        let name = locale_dir.file_name().unwrap_or_default().to_string_lossy();
+        let langid = match parse_langid(&name) {
+            Ok(langid) => langid,
+            Err(err) => return syn::Error::new_spanned(path, err).to_compile_error().into(),
+        };
+        let display_name = match &display_name {
+            Some(id) => match find_display_name(&locale_dir, &id.value()) {
+                Ok(display_name) => display_name,
+                Err(err) => return syn::Error::new_spanned(path, err).to_compile_error().into(),
+            },
+            None => None,
+        };
+        let files = match ftl::ftl_files(&locale_dir) {
+            Ok(files) => files,
+            Err(err) => return syn::Error::new_spanned(path, err).to_compile_error().into(),
+        };
+        locales.push(LocaleDir {
+            langid,
+            display_name,
+            files: files
+                .iter()
+                .map(|file| file.to_string_lossy().into_owned())
+                .collect(),
+        });
+    }
+
+    let langids = locales.iter().map(|locale| &locale.langid);
+    let (named, names): (Vec<_>, Vec<_>) = locales
+        .iter()
+        .filter_map(|locale| {
+            let (name, _) = locale.display_name.as_ref()?;
+            Some((&locale.langid, name))
+        })
+        .unzip();
+    // recompile whenever a file of a locale changes, or a locale is removed
+    let files = locales.iter().flat_map(|locale| &locale.files);
+
+    TokenStream::from(quote! {
+        #(const _: &[u8] = include_bytes!(#files);)*
+
+        /// The language identifiers of the locale directories, sorted.
+        pub static AVAILABLE_LOCALES: ::std::sync::LazyLock<::std::vec::Vec<i18n::LanguageIdentifier>> =
+            ::std::sync::LazyLock::new(|| {
+                let langids: &[&str] = &[#(#langids),*];
+                langids
+                    .iter()
+                    .map(|langid| langid.parse().expect("parsed when expanding `available_locales!`"))
+                    .collect()
+            });
+
+        /// Returns the language identifiers of the locale directories, sorted,
+        /// e.g. for `provide_available_locales`.
+        pub fn available_locales() -> &'static [i18n::LanguageIdentifier] {
+            &AVAILABLE_LOCALES
+        }
+
+        /// Returns the name of `langid` in its own language, e.g. for a language switcher,
+        /// if its locale directory has one.
+        pub fn locale_display_name(langid: &i18n::LanguageIdentifier) -> ::std::option::Option<&'static str> {
+            match langid.to_string().as_str() {
+                #(#named => ::std::option::Option::Some(#names),)*
+                _ => ::std::option::Option::None,
+            }
+        }
+    })
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// The environment variable pointing to the directory with the default locale's FTL files.
+/// Relative paths are resolved against the manifest directory of the crate being compiled.
+pub const FTL_DIR_ENV: &str = "I18N_LEPTOS_FTL_DIR";
+
+/// A message found in an FTL file.
+#[derive(Debug, Default)]
+pub struct FtlMessage {
+    pub attrs: BTreeSet<String>,
+    /// The variables referenced by the message's value, without the leading `$`.
+    pub vars: BTreeSet<String>,
+    /// The variables the message's value selects a variant on, e.g. `count` for
+    /// `{ $count -> ... }` or `{ NUMBER($count) -> ... }`.
+    pub selectors: BTreeSet<String>,
+    /// The selectors with variants for plural categories or numbers, e.g. `count` for
+    /// `{ $count -> [one] ... *[other] ... }`, which need a number to select the right one.
+    pub numeric_selectors: BTreeSet<String>,
+    /// The messages referenced by the message's value, e.g. `other` for `{ other }`.
+    pub refs: BTreeSet<String>,
+}
+
+/// An index of the messages defined in a set of FTL files.
+///
+/// This only scans the structure of the files (message IDs and attribute names)
+/// and doesn't validate their syntax, which is left to Fluent at runtime.
+#[derive(Debug, Default)]
+pub struct FtlIndex {
+    pub messages: BTreeMap<String, FtlMessage>,
+    /// The FTL files the index was loaded from, which the expansions validated against it
+    /// depend on.
+    pub files: Vec<PathBuf>,
+}
+
+impl FtlIndex {
+    /// Loads every `.ftl` file in `dir` and its subdirectories.
+    pub fn load_dir(dir: &Path) -> Result<Self, String> {
+        let mut index = FtlIndex::default();
+        index.add_dir(dir)?;
+        Ok(index)
+    }
+
+    /// Loads a single FTL file.
+    pub fn load_file(path: &Path) -> Result<Self, String> {
+        let mut index = FtlIndex::default();
+        index.add_file(path)?;
+        Ok(index)
+    }
+
+    fn add_dir(&mut self, dir: &Path) -> Result<(), String> {
+        for path in ftl_files(dir)? {
+            self.add_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn add_file(&mut self, path: &Path) -> Result<(), String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read FTL file '{}': {err}", path.display()))?;
+        self.add_source(&source);
+        self.files.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Returns a cycle of message references the message `id` depends on, which Fluent
+    /// fails to resolve, as the IDs along the cycle, e.g. `["a", "b", "a"]`.
+    pub fn find_cycle(&self, id: &str) -> Option<Vec<String>> {
+        self.find_cycle_from(id, &mut Vec::new(), &mut BTreeSet::new())
+    }
+
+    fn find_cycle_from<'a>(
+        &'a self,
+        id: &'a str,
+        path: &mut Vec<&'a str>,
+        visited: &mut BTreeSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|other| *other == id) {
+            let mut cycle: Vec<_> = path[start..].iter().map(|id| id.to_string()).collect();
+            cycle.push(id.to_string());
+            return Some(cycle);
+        }
+        if !visited.insert(id) {
+            return None;
+        }
+        let message = self.messages.get(id)?;
+        path.push(id);
+        for reference in &message.refs {
+            if let Some(cycle) = self.find_cycle_from(reference, path, visited) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        None
+    }
+
+    /// Adds the messages of an FTL source to the index.
+    pub fn add_source(&mut self, source: &str) {
+        let mut current: Option<String> = None;
+        // the nesting of placeables in the current message's value, which may span lines,
+        // or `None` once its attributes start
+        let mut value_depth: Option<usize> = None;
+        // the variable selected on by the last select expression of the current message
+        let mut selector: Option<String> = None;
+        for line in source.lines() {
+            let Some(first) = line.chars().next() else {
+                // blank lines may appear within multiline patterns
+                continue;
+            };
+            if first == ' ' || first == '\t' {
+                // an indented line is either a continuation or an attribute of the current message
+                let Some(id) = &current else { continue };
+                let message = self.messages.entry(id.clone()).or_default();
+                if let Some(attr) = line.trim_start().strip_prefix('.')
+                    && let Some(name) = parse_identifier(attr)
+                {
+                    message.attrs.insert(name.to_string());
+                    value_depth = None;
+                } else if let Some(depth) = &mut value_depth {
+                    scan_variables(line, depth, &mut selector, message);
+                }
+            } else if let Some(id) = parse_identifier(line) {
+                let message = self.messages.entry(id.to_string()).or_default();
+                let mut depth = 0;
+                selector = None;
+                scan_variables(&line[id.len()..], &mut depth, &mut selector, message);
+                value_depth = Some(depth);
+                current = Some(id.to_string());
+            } else {
+                // comments, terms and junk end the current message
+                current = None;
+            }
+        }
+    }
+}
+
+/// Parses a Fluent identifier followed by `=` at the start of `line`.
+fn parse_identifier(line: &str) -> Option<&str> {
+    let end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(line.len());
+    let (id, rest) = line.split_at(end);
+    let starts_with_letter = id.starts_with(|c: char| c.is_ascii_alphabetic());
+    (starts_with_letter && rest.trim_start().starts_with('=')).then_some(id)
+}
+
+/// Adds the variables referenced within placeables in `line` to the `message`, keeping
+/// track of the nesting of placeables across lines in `depth`. A `$` outside of placeables
+/// is text. The last variable before a `->` is the one a select expression selects on.
+/// Placeables starting with an identifier that isn't a function call reference a message.
+/// The selector of the last select expression is kept in `selector` across lines, to tell
+/// whether its variant keys are numeric.
+fn scan_variables(
+    line: &str,
+    depth: &mut usize,
+    selector: &mut Option<String>,
+    message: &mut FtlMessage,
+) {
+    let mut last_var = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '{' => {
+                *depth += 1;
+                let rest = line[i + 1..].trim_start();
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                    .unwrap_or(rest.len());
+                if rest.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && !rest[end..].starts_with('(')
+                {
+                    message.refs.insert(rest[..end].to_string());
+                }
+            }
+            '}' => *depth = depth.saturating_sub(1),
+            '$' if *depth > 0 => {
+                let rest = &line[i + 1..];
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                    .unwrap_or(rest.len());
+                if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    message.vars.insert(rest[..end].to_string());
+                    last_var = Some(&rest[..end]);
+                }
+            }
+            '-' if *depth > 0 && line[i..].starts_with("->") => {
+                if let Some(var) = last_var.take() {
+                    message.selectors.insert(var.to_string());
+                    *selector = Some(var.to_string());
+                }
+            }
+            '[' if *depth > 0 => {
+                let key = line[i + 1..].split(']').next().unwrap_or_default().trim();
+                if let Some(selector) = selector
+                    && is_numeric_variant_key(key)
+                {
+                    message.numeric_selectors.insert(selector.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns whether the variant key `key` is a CLDR plural category other than `other`,
+/// or a number, which only numbers select.
+fn is_numeric_variant_key(key: &str) -> bool {
+    matches!(key, "zero" | "one" | "two" | "few" | "many") || key.parse::<f64>().is_ok()
+}
+
+/// Resolves `path` against the manifest directory of the crate being compiled.
+pub fn resolve_path(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) if path.is_relative() => PathBuf::from(manifest_dir).join(path),
+        _ => path,
+    }
+}
+
+/// Returns every `.ftl` file in `dir` and its subdirectories, sorted.
+pub fn ftl_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| format!("failed to read FTL directory '{}': {err}", dir.display()))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(ftl_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "ftl") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// The state of an FTL file when it was indexed.
+type FileStamp = (PathBuf, Option<SystemTime>, u64);
+
+/// Returns the state of every FTL file in `dir`, which changes whenever one of them is added,
+/// removed or modified.
+fn file_stamps(dir: &Path) -> Result<Vec<FileStamp>, String> {
+    let files = ftl_files(dir)?;
+    Ok(files
+        .into_iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(&path).ok();
+            let modified = metadata
+                .as_ref()
+                .and_then(|metadata| metadata.modified().ok());
+            let len = metadata.map_or(0, |metadata| metadata.len());
+            (path, modified, len)
+        })
+        .collect())
+}
+
+/// Returns the index of the FTL directory set in `I18N_LEPTOS_FTL_DIR`,
+/// or `None` if the variable isn't set and validation is disabled.
+///
+/// The index is cached per directory, but reloaded once any of its files was added, removed
+/// or modified, as a proc macro server (like rust-analyzer's) lives across edits.
+pub fn default_locale_index() -> Option<Result<Arc<FtlIndex>, String>> {
+    static INDEXES: Mutex<Option<HashMap<PathBuf, (Vec<FileStamp>, Arc<FtlIndex>)>>> =
+        Mutex::new(None);
+
+    let dir = resolve_path(&std::env::var(FTL_DIR_ENV).ok()?);
+    let stamps = match file_stamps(&dir) {
+        Ok(stamps) => stamps,
+        Err(err) => return Some(Err(err)),
+    };
+    let mut indexes = INDEXES.lock().unwrap_or_else(|err| err.into_inner());
+    let indexes = indexes.get_or_insert_with(HashMap::new);
+    if let Some((cached, index)) = indexes.get(&dir)
+        && *cached == stamps
+    {
+        return Some(Ok(index.clone()));
+    }
+
+    let index = match FtlIndex::load_dir(&dir) {
+        Ok(index) => Arc::new(index),
+        Err(err) => return Some(Err(err)),
+    };
+    indexes.insert(dir, (stamps, index.clone()));
+    Some(Ok(index))
+}
+
+/// Makes the expansion depend on every file of `index`, so that editing the FTL files
+/// recompiles the crate and validates it again.
+pub fn track_files(index: &FtlIndex) -> proc_macro2::TokenStream {
+    let files = index
+        .files
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned());
+    quote::quote! {
+        #(const _: &[u8] = include_bytes!(#files);)*
+    }
+}
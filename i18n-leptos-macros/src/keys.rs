@@ -0,0 +1,301 @@
+use crate::ftl::{self, FtlIndex, FtlMessage};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Ident, LitStr, Token};
+
+struct FtlKeysMacroInput {
+    path: LitStr,
+    locales_var: Ident,
+    module: Ident,
+}
+
+impl Parse for FtlKeysMacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path: LitStr = input.parse()?;
+        let mut locales_var = Ident::new("LOCALES", Span::call_site());
+        let mut module = Ident::new("keys", Span::call_site());
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: LitStr = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.value().as_str() {
+                "locales" => locales_var = input.parse()?,
+                "module" => module = input.parse()?,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "Expected 'locales' or 'module'",
+                    ));
+                }
+            }
+        }
+
+        Ok(FtlKeysMacroInput {
+            path,
+            locales_var,
+            module,
+        })
+    }
+}
+
+/// Converts a Fluent identifier like `field-name` into `FieldName`.
+fn to_upper_camel_case(id: &str) -> String {
+    id.split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+/// Converts a Fluent identifier like `aria-label` into an identifier usable as a field name,
+/// escaping keywords.
+fn to_field_ident(id: &str) -> Ident {
+    let name = id.replace('-', "_").to_ascii_lowercase();
+    match syn::parse_str::<Ident>(&name) {
+        Ok(ident) => ident,
+        Err(_) => Ident::new_raw(&name, Span::call_site()),
+    }
+}
+
+/// Returns the name of the type generated for a message.
+fn type_name(id: &str) -> String {
+    to_upper_camel_case(id)
+}
+
+/// Returns the name of the constant generated for a message.
+fn const_name(id: &str) -> String {
+    id.replace('-', "_").to_ascii_uppercase()
+}
+
+/// Checks that no two messages generate the same type or constant, and that no two attributes
+/// of a message generate the same field, e.g. `foo-bar` and `foo_bar` both generating `FooBar`,
+/// or `x-args` generating the `XArgs` type of the arguments of `x`.
+fn check_name_collisions(index: &FtlIndex) -> std::result::Result<(), String> {
+    let mut types = HashMap::new();
+    let mut consts = HashMap::from([("ALL_MESSAGE_IDS".to_string(), None)]);
+
+    for (id, message) in &index.messages {
+        let args_name = (!message.vars.is_empty()).then(|| format!("{}Args", type_name(id)));
+        let names = [
+            (false, Some(type_name(id))),
+            (true, Some(const_name(id))),
+            (false, args_name),
+        ];
+        for (is_const, name) in names {
+            let Some(name) = name else {
+                continue;
+            };
+            let generated = if is_const { &mut consts } else { &mut types };
+            match generated.insert(name.clone(), Some(id)) {
+                None => {}
+                Some(Some(other)) => {
+                    return Err(format!(
+                        "Messages '{other}' and '{id}' both generate `{name}`, rename one of them."
+                    ));
+                }
+                Some(None) => {
+                    return Err(format!(
+                        "Message '{id}' generates `{name}`, which is reserved, rename it."
+                    ));
+                }
+            }
+        }
+
+        let mut fields = HashMap::new();
+        for attr in &message.attrs {
+            let field = to_field_ident(attr).to_string();
+            if let Some(other) = fields.insert(field.clone(), attr) {
+                return Err(format!(
+                    "Attributes '{other}' and '{attr}' of message '{id}' both generate the field \
+                     `{field}`, rename one of them."
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generates a struct with a field for every variable of a message, which implements
+/// `MessageArgs` so that it can be passed to `rtr!` as `args`.
+fn message_args(id: &str, type_name: &Ident, message: &FtlMessage) -> proc_macro2::TokenStream {
+    let args_name = format_ident!("{type_name}Args");
+    let doc = format!("The arguments of the `{id}` message, for passing to `rtr!` as `args`.");
+    let fields: Vec<_> = message.vars.iter().map(|var| to_field_ident(var)).collect();
+    let params: Vec<_> = message
+        .vars
+        .iter()
+        .map(|var| format_ident!("{}", to_upper_camel_case(var)))
+        .collect();
+    let vars = message.vars.iter();
+    let field_docs = message
+        .vars
+        .iter()
+        .map(|var| format!("The value of the `${var}` variable."));
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct #args_name<#(#params),*> {
+            #(
+                #[doc = #field_docs]
+                pub #fields: #params,
+            )*
+        }
+
+        impl<#(#params),*> i18n_leptos::MessageArgs for #args_name<#(#params),*>
+        where
+            #(#params: Clone + Into<i18n::FluentValue<'static>>,)*
+        {
+            fn add_to_query(&self, query: i18n::Query) -> i18n::Query {
+                query
+                    #(.with_arg(#vars, i18n_leptos::__private::arg(Clone::clone(&self.#fields))))*
+            }
+        }
+    }
+}
+
+pub fn ftl_keys_impl(input: TokenStream) -> TokenStream {
+    let FtlKeysMacroInput {
+        path,
+        locales_var,
+        module,
+    } = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let full_path = ftl::resolve_path(&path.value());
+    let index = match FtlIndex::load_file(&full_path) {
+        Ok(index) => index,
+        Err(err) => return syn::Error::new_spanned(path, err).to_compile_error().into(),
+    };
+    if let Err(err) = check_name_collisions(&index) {
+        return syn::Error::new_spanned(path, err).to_compile_error().into();
+    }
+    let full_path = full_path.to_string_lossy().into_owned();
+
+    let messages = index.messages.iter().map(|(id, message)| {
+        let type_name = format_ident!("{}", type_name(id));
+        let const_name = format_ident!("{}", const_name(id));
+        let doc = format!("The `{id}` message.");
+        let attr_fields: Vec<_> = message
+            .attrs
+            .iter()
+            .map(|attr| to_field_ident(attr))
+            .collect();
+        let attr_names: Vec<_> = message.attrs.iter().collect();
+        let attr_docs = message
+            .attrs
+            .iter()
+            .map(|attr| format!("The name of the `{id}.{attr}` attribute."));
+        let args = (!message.vars.is_empty()).then(|| message_args(id, &type_name, message));
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct #type_name {
+                #(
+                    #[doc = #attr_docs]
+                    pub #attr_fields: &'static str,
+                )*
+            }
+
+            impl #type_name {
+                /// Returns the message ID.
+                pub const fn id(&self) -> &'static str {
+                    #id
+                }
+            }
+
+            impl i18n_leptos::ReactiveLocalizedDisplay for #type_name {
+                fn reactive_localize(self) -> i18n_leptos::ReactiveMessage {
+                    i18n_leptos::__private::reactive_query(#id, move |langid| {
+                        i18n_leptos::__private::query(&#locales_var, langid, #id, &i18n::Query::new(#id))
+                    })
+                }
+            }
+
+            #[doc = #doc]
+            pub const #const_name: #type_name = #type_name {
+                #(#attr_fields: #attr_names,)*
+            };
+
+            #args
+        }
+    });
+
+    let ids = index.messages.keys();
+
+    TokenStream::from(quote! {
+        #[allow(dead_code)]
+        pub mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            // recompile whenever the FTL file changes
+            const _: &[u8] = include_bytes!(#full_path);
+
+            /// The IDs of every message in the FTL file, sorted.
+            pub const ALL_MESSAGE_IDS: &[&str] = &[#(#ids),*];
+
+            #(#messages)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collisions(source: &str) -> std::result::Result<(), String> {
+        let mut index = FtlIndex::default();
+        index.add_source(source);
+        check_name_collisions(&index)
+    }
+
+    #[test]
+    fn distinct_names_are_accepted() {
+        let source =
+            "foo-bar = Foo\nfoobar = Foo\nx = { $n }\n    .aria-label = X\n    .title = X\n";
+        assert_eq!(collisions(source), Ok(()));
+    }
+
+    #[test]
+    fn messages_generating_the_same_type_are_rejected() {
+        let err = collisions("foo-bar = Foo\nfoo_bar = Foo\n").unwrap_err();
+        assert!(err.contains("'foo-bar'"), "{err}");
+        assert!(err.contains("'foo_bar'"), "{err}");
+        assert!(err.contains("`FooBar`"), "{err}");
+    }
+
+    #[test]
+    fn messages_generating_the_args_type_of_another_are_rejected() {
+        let err = collisions("x = { $n }\nx-args = X\n").unwrap_err();
+        assert!(err.contains("'x'"), "{err}");
+        assert!(err.contains("'x-args'"), "{err}");
+        assert!(err.contains("`XArgs`"), "{err}");
+    }
+
+    #[test]
+    fn messages_generating_a_reserved_constant_are_rejected() {
+        let err = collisions("all-message-ids = All\n").unwrap_err();
+        assert!(err.contains("`ALL_MESSAGE_IDS`"), "{err}");
+    }
+
+    #[test]
+    fn attributes_generating_the_same_field_are_rejected() {
+        let err = collisions("x = X\n    .aria-label = A\n    .aria_label = B\n").unwrap_err();
+        assert!(err.contains("'aria-label'"), "{err}");
+        assert!(err.contains("'aria_label'"), "{err}");
+    }
+}
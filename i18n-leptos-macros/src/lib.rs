@@ -1,7 +1,14 @@
 use proc_macro::TokenStream;
 
+mod available;
+mod ftl;
+mod keys;
+mod localize;
+mod localized;
 mod rattr;
 mod rtr;
+mod selector;
+mod usage;
 
 /// A reactive procedural macro for internationalization in Leptos applications.
 ///
@@ -14,9 +21,9 @@ mod rtr;
 /// 1.  **Message ID Lookup**: Translates a message ID (string literal) using the
 ///     current language from the Leptos context. This mode supports arguments.
 /// 2.  **LocalizedDisplay Object**: Calls the `.reactive_localize()` method on an
-///     object that implements the `LocalizedDisplay` trait. This mode does not
-///     support additional arguments within the macro itself,
-///     as the `LocalizedDisplay` implementation is expected to handle its own
+///     object that implements the `LocalizedDisplay` trait. This mode only supports
+///     plain `"key" = value` arguments, which are passed through to objects implementing
+///     `LocalizedDisplayWithArgs`, as the implementation is expected to handle its own
 ///     localization logic.
 ///
 /// Both modes return a `i18n_leptos::ReactiveMessage`, ensuring that
@@ -29,26 +36,129 @@ mod rtr;
 /// ## Syntax
 /// ```ignore
 /// // Mode 1: Message ID Lookup
-/// rtr!("message-id" [, locales = VAR_NAME] [, key = value]* [, attr("attr-id", key = value)* ]);
+/// rtr!(
+///     "message-id"
+///     [, "locales" = VAR_NAME]
+///     [, "args" = fluent_args]
+///     [, "plural" = n | "plural_signal" = count [, "plural_arg" = "name"]]
+///     [, "ordinal" = n [, "ordinal_arg" = "name"]]
+///     [, "get_attr" = "attr-id"]
+///     [, "default" = "fallback"]
+///     [, "langid" = langid_expr]
+///     [, "select_name" = selector]*
+///     [, "key" = value]*
+///     [, attr("attr-id", "key" = value [, "key" = value]*)]*
+/// );
 ///
 /// // Mode 2: LocalizedDisplay Object
-/// rtr!(localized_object_expr);
+/// rtr!(localized_object_expr [, "get_attr" = "attr-id"] [, "key" = value]*);
 /// ```
 ///
 /// ### Parameters
 /// -   **`"message-id"`**: A string literal representing the ID of the Fluent message to translate.
 /// -   **`localized_object_expr`**: An expression that evaluates to an object implementing
-///     the `LocalizedDisplay` trait. When this is used, no other parameters are allowed.
-/// -   **`locales = VAR_NAME`** (optional, Mode 1 only): An identifier for the
+///     the `LocalizedDisplay` trait. When this is used, only `get_attr` and `"key" = value`
+///     arguments are allowed. With arguments, the object has to implement
+///     `i18n_leptos::LocalizedDisplayWithArgs` instead, which receives them.
+/// -   **`"locales" = VAR_NAME`** (optional, Mode 1 only): An identifier for the
 ///     `i18n::Locales` static variable to use. Defaults to `LOCALES`.
-/// -   **`key = value`** (optional, Mode 1 only): Key-value pairs for arguments to the
-///     main message. `key` must be a string literal, and `value` can be any Rust expression.
-/// -   **`attr("attr-id", key = value)`** (optional, Mode 1 only): Arguments for a
-///     specific attribute of the message. `"attr-id"` is a string literal representing
-///     the attribute ID. `key` must be a string literal, and `value` can be any Rust expression.
+/// -   **`"args" = fluent_args`** (optional, Mode 1 only): An expression evaluating to
+///     `i18n::FluentArgs<'static>` built at runtime, for argument sets not known at compile
+///     time, or to any other `i18n_leptos::MessageArgs`, like the typed argument structs
+///     generated by `ftl_keys!`, merged with the inline arguments, which win on conflict.
+/// -   **`"plural" = n`** (optional, Mode 1 only): A number passed as a numeric Fluent value
+///     named `count`, so that `{ $count -> ... }` selectors pick the CLDR plural category
+///     of the active locale (e.g. `one`/`few`/`many`/`other` in Polish). `n` must convert
+///     into `i18n::FluentNumber`.
+/// -   **`"plural_signal" = count`** (optional, Mode 1 only): Like `plural`, but takes a signal
+///     of the number (e.g. a `Signal<i64>`), so that the message is re-resolved whenever it
+///     changes, updating both the number shown and the plural form: "1 item", "2 items".
+/// -   **`"plural_arg" = "name"`** (optional, Mode 1 only): Renames the argument set by `plural`
+///     or `plural_signal`.
+/// -   **`"ordinal" = n`** (optional, Mode 1 only): Like `plural`, but the argument is named
+///     `place`, and selectors match the CLDR ordinal category of the active locale instead
+///     (e.g. `one`/`two`/`few`/`other` for "1st"/"2nd"/"3rd"/"4th" in English), as in
+///     `{ $place -> [one] {$place}st ... }`. It can be combined with `plural` as long as
+///     their arguments are named differently.
+/// -   **`"ordinal_arg" = "name"`** (optional, Mode 1 only): Renames the argument set by `ordinal`.
+/// -   **`"get_attr" = "attr-id"`** (optional): Returns only the given attribute of the message
+///     as a `Signal<String>` instead of the whole `ReactiveMessage`, e.g. for binding the
+///     `.placeholder` of a form field label directly to an input.
+/// -   **`"default" = "fallback"`** (optional, Mode 1 only): The value used if the message can't
+///     be resolved, instead of the one given by the `FallbackPolicy` (the message ID by default),
+///     e.g. for strings that have no FTL entry yet. Any expression converting into a `String`.
+/// -   **`"langid" = langid_expr`** (optional, Mode 1 only): Resolves the message in the given
+///     language instead of the one of the context, e.g. for a disclaimer always shown in the
+///     account's language. Any expression converting into a `Signal<LanguageIdentifier>`,
+///     so passing a signal keeps the message reactive to it.
+/// -   **`"select_name" = selector`** (optional, Mode 1 only): Passes the variant key of a value
+///     implementing `i18n_leptos::FluentSelector` as the argument `name`, e.g.
+///     `"select_gender" = gender` for a `{ $gender -> ... }` selector.
+/// -   **`"key" = value`** (optional): Key-value pairs for arguments to the main message,
+///     or to the object in Mode 2. `"key"` is a string literal, so any Fluent argument name
+///     can be passed, including hyphenated ones like `"user-name" = name`, and `value` can
+///     be any Rust expression. Keys only known at runtime go into `args`. In Mode 1, string
+///     values are wrapped in bidi isolation marks if `BidiIsolation::Isolate` is provided.
+/// -   **`attr("attr-id", "key" = value [, "key" = value]*)`** (optional, Mode 1 only):
+///     Arguments for a specific attribute of the message, which don't apply to the main
+///     message. `"attr-id"` is a string literal representing the attribute ID. `"key"` is a
+///     string literal, and `value` can be any Rust expression. The same attribute may be given
+///     several groups, whose arguments are merged.
+///
+/// ### Argument order
+/// After the message ID or object, arguments may come in any order and combination, e.g.
+/// `rtr!("id", attr("title", "name" = name), "default" = "Hi", "locales" = APP_LOCALES)` is the
+/// same as `rtr!("id", "locales" = APP_LOCALES, "default" = "Hi", attr("title", "name" = name))`,
+/// and a message may have attribute arguments without any main arguments. Each special key
+/// (`locales`, `args`, `plural`/`plural_signal`, `plural_arg`, `ordinal`, `ordinal_arg`,
+/// `get_attr`, `default`, `langid`) may only be passed once, and the same argument name only
+/// once per message or attribute, which is a compile error otherwise.
+///
+/// ## Reactive owner
+/// Calls must run within a reactive owner that has the `LangIdContext`, e.g. in a component
+/// body, and panic otherwise, pointing at the call. Outside of one, e.g. in event handlers or
+/// at initialization, use `i18n_leptos::translate_in` or read a message created beforehand.
+///
+/// ## Reactivity
+/// Each call creates a single memo, which only re-resolves the message when the language
+/// changes, when locales are added with `i18n_leptos::add_locales`, or when a signal read by
+/// an argument expression changes, e.g. `"count" = count.get()`. Arguments are evaluated
+/// again on each re-resolution, but arguments without signals never cause one, so a message
+/// with static arguments is only re-resolved on language changes, however often the
+/// enclosing component re-renders. Fallback policies and error handlers run untracked.
+///
+/// ## Caching
+/// Messages looked up without any arguments are resolved once per language and then served
+/// from a cache, which can be warmed ahead of time with `i18n_leptos::preload_messages`.
+///
+/// ## Compile-time validation
+/// If the `I18N_LEPTOS_FTL_DIR` environment variable is set at build time (e.g. through the
+/// `[env]` section of `.cargo/config.toml`), it is read as the directory containing the
+/// default locale's `.ftl` files, relative to the crate's manifest directory. Message IDs
+/// and attribute names passed to `rtr!` are then checked against it, turning typos like
+/// `"welcom-message"` into compile errors. `plural`, `ordinal` and `select_*` arguments the
+/// message doesn't select a variant on, e.g. `"plural" = n` for a message without a
+/// `{ $count -> ... }` selector, emit a warning. String literals passed for an argument the
+/// message selects a plural category on, e.g. `"count" = "5"` for `{ $count -> [one] ... }`,
+/// are a compile error, as only numbers select those variants. Editing the FTL files
+/// recompiles the crates validated against them.
+///
+/// ## Usage report
+/// If the `I18N_LEPTOS_USAGE_DIR` environment variable is set at build time, every message ID
+/// and attribute referenced by `rtr!` is written to a file in that directory as a line of
+/// tab-separated values: the crate name, the `file:line:column` of the call, and the message
+/// ID, followed by `.attr` for attributes. Tools can merge the files and compare them against
+/// the FTL files to find unused and undefined messages. Relative paths are resolved against the
+/// `OUT_DIR` of the crate if it has a build script, and against its manifest directory otherwise.
+/// Failing to write a file is a warning.
+///
+/// Each call has its own file, which is rewritten whenever the call is expanded, so the report
+/// stays correct across incremental builds and proc macro servers like rust-analyzer's. The
+/// files of calls that were moved or removed are left behind though, so clear the directory
+/// (e.g. with `cargo clean`) before a build that the report is taken from.
 ///
 /// ## Returns
-/// A `i18n_leptos::ReactiveMessage`.
+/// A `i18n_leptos::ReactiveMessage`, or a `Signal<String>` if `get_attr` is passed.
 #[proc_macro]
 pub fn rtr(input: TokenStream) -> TokenStream {
     rtr::rtr_impl(input)
@@ -61,14 +171,28 @@ pub fn rtr(input: TokenStream) -> TokenStream {
 ///
 /// ## Syntax
 /// ```ignore
-/// rattr!(reactive_message, "attribute-name" [, key = value]*);
+/// rattr!(
+///     reactive_message, "attribute-name"
+///     [, "plural" = n [, "plural_arg" = "name"]]
+///     [, "key" = value]*
+///     [, "default" = "fallback"]
+/// );
 /// ```
 ///
 /// ### Parameters
 /// -   **`reactive_message`**: An expression that evaluates to a `ReactiveMessage`.
 /// -   **`"attribute-name"`**: A string literal representing the name of the attribute to retrieve.
-/// -   **`key = value`** (optional): Key-value pairs for arguments to the attribute.
-///     `key` must be a string literal, and `value` can be any Rust expression.
+/// -   **`"key" = value`** (optional): Key-value pairs for arguments to the attribute.
+///     `"key"` is a string literal, and `value` can be any Rust expression.
+/// -   **`"plural" = n`** (optional): A number passed as a numeric Fluent value named `count`,
+///     so that a `{ $count -> ... }` selector in the attribute picks the plural category of
+///     the active locale, like the `plural` argument of `rtr!`.
+/// -   **`"plural_arg" = "name"`** (optional): Renames the argument set by `plural`.
+/// -   **`"default" = "fallback"`** (optional): The value returned if the attribute is missing
+///     or fails to localize, instead of the attribute name. Any expression of a `&str`-like type.
+///
+/// Like with `rtr!`, each special key and each argument name may only be passed once, and the
+/// argument set by `plural` mustn't be passed as well, which is a compile error otherwise.
 ///
 /// ## Returns
 /// A `String` representing the value of the attribute.
@@ -76,3 +200,163 @@ pub fn rtr(input: TokenStream) -> TokenStream {
 pub fn rattr(input: TokenStream) -> TokenStream {
     rattr::rattr_impl(input)
 }
+
+/// A macro generating typed constants for the messages of an FTL file.
+///
+/// For every message, a `Copy` type with a constant of it is generated in a module,
+/// so that message IDs are autocompletable and renaming a message in the FTL file
+/// without updating its usages fails to compile. The types implement
+/// `ReactiveLocalizedDisplay`, so the constants can be passed to `rtr!` directly,
+/// and carry the names of the message's attributes as fields.
+///
+/// ## Syntax
+/// ```ignore
+/// ftl_keys!("path/to/file.ftl" [, locales = VAR_NAME] [, module = name]);
+///
+/// // given a `field-name` message with a `.placeholder` attribute
+/// let label = rtr!(keys::FIELD_NAME);
+/// let placeholder = label.attr(keys::FIELD_NAME.placeholder, None);
+/// ```
+///
+/// ### Parameters
+/// -   **`"path/to/file.ftl"`**: The FTL file to generate the constants for, relative to the
+///     crate's manifest directory.
+/// -   **`locales = VAR_NAME`** (optional): An identifier for the `i18n::Locales` static
+///     variable to query, which must be in scope of the invocation. Defaults to `LOCALES`.
+/// -   **`module = name`** (optional): The name of the generated module. Defaults to `keys`.
+///
+/// ## Naming
+/// A message `field-name` generates a `FieldName` type and a `FIELD_NAME` constant.
+/// Its attributes become fields in snake case, e.g. `aria-label` becomes `aria_label`.
+/// Messages or attributes generating the same name, e.g. `foo-bar` and `foo_bar`, fail to compile
+/// with an error naming both.
+/// The module also contains an `ALL_MESSAGE_IDS` constant listing every message ID of the file,
+/// e.g. for passing to `i18n_leptos::preload_messages`.
+///
+/// ## Arguments
+/// A message referencing variables also generates a struct with a field for each of them,
+/// e.g. `WelcomeArgs { name, count }` for `welcome = Hi { $name }, { $count } new messages`.
+/// Each field takes any value converting into a Fluent value, and passing the struct to
+/// `rtr!` as `args` fails to compile if one is missing or misspelled:
+/// ```ignore
+/// let args = keys::WelcomeArgs { name: user.name.clone(), count: 3 };
+/// let welcome = rtr!("welcome", "args" = args);
+/// ```
+#[proc_macro]
+pub fn ftl_keys(input: TokenStream) -> TokenStream {
+    keys::ftl_keys_impl(input)
+}
+
+/// A macro generating the list of locales an app ships from its locales directory,
+/// so that it can't drift from the actual FTL files.
+///
+/// Every subdirectory of the directory is taken as a locale named by its language
+/// identifier, e.g. `locales/en-US` and `locales/fr-FR`. Subdirectories whose names aren't
+/// valid language identifiers, like `locales/fallbacks`, are a compile error.
+///
+/// ## Syntax
+/// ```ignore
+/// available_locales!("path/to/locales" [, display_name = "message-id"]);
+///
+/// provide_available_locales(available_locales());
+/// ```
+///
+/// ### Parameters
+/// -   **`"path/to/locales"`**: The locales directory, relative to the crate's manifest directory.
+/// -   **`display_name = "message-id"`** (optional): A message each locale defines with its
+///     name in its own language, e.g. `language-name = Français`, read from the `.ftl` files
+///     directly in its directory. Only plain single-line values are supported.
+///
+/// ## Generated items
+/// -   `AVAILABLE_LOCALES`: A static of the `LanguageIdentifier`s of the locales, sorted.
+/// -   `available_locales()`: The same as a `&'static [LanguageIdentifier]`, e.g. for
+///     `i18n_leptos::provide_available_locales`.
+/// -   `locale_display_name(&langid)`: The display name of a locale, if it has one.
+///
+/// Editing the FTL files of a locale or removing it triggers a recompilation, but adding
+/// a locale directory doesn't by itself.
+#[proc_macro]
+pub fn available_locales(input: TokenStream) -> TokenStream {
+    available::available_locales_impl(input)
+}
+
+/// Derives `i18n_leptos::FluentSelector` for an enum of unit variants.
+///
+/// Each variant selects its name in kebab case (e.g. `NonBinary` selects `non-binary`),
+/// which can be overridden with a `#[selector("key")]` attribute.
+///
+/// ## Example
+/// ```ignore
+/// #[derive(FluentSelector)]
+/// enum Gender {
+///     Male,
+///     Female,
+///     #[selector("other")]
+///     Unspecified,
+/// }
+///
+/// let greeting = rtr!("greeting", select_gender = Gender::Female);
+/// ```
+#[proc_macro_derive(FluentSelector, attributes(selector))]
+pub fn derive_fluent_selector(input: TokenStream) -> TokenStream {
+    selector::derive_fluent_selector_impl(input)
+}
+
+/// Derives `i18n::LocalizedDisplay` for an enum, so that its values can be passed to `rtr!`
+/// directly through the `ReactiveLocalizedDisplay` path.
+///
+/// Each variant is localized as the message named after it in kebab case (e.g. `NotFound`
+/// as `not-found`), which can be overridden with a `#[msg("message-id")]` attribute.
+/// The fields of a variant are passed as arguments named after them, which can be renamed
+/// with an `#[arg("name")]` attribute, required for the fields of tuple variants.
+/// Field types must be `Clone` and convert into Fluent values.
+///
+/// The messages are queried from the `LOCALES` static in scope of the enum, or the one
+/// given with a `#[locales(VAR_NAME)]` attribute on the enum.
+///
+/// ## Example
+/// ```ignore
+/// #[derive(LocalizedDisplay)]
+/// enum Status {
+///     Online,
+///     #[msg("status-away")]
+///     Away { minutes: u32 },
+///     Busy(#[arg("reason")] String),
+/// }
+///
+/// let status = rtr!(Status::Away { minutes: 5 });
+/// ```
+#[proc_macro_derive(LocalizedDisplay, attributes(msg, arg, locales))]
+pub fn derive_localized_display(input: TokenStream) -> TokenStream {
+    localized::derive_localized_display_impl(input)
+}
+
+/// Derives a `localize()` function for a struct with named fields, e.g. a form, returning a
+/// struct with a `ReactiveMessage` for each field, so that the field-to-message mapping is
+/// kept in one place instead of repeating `rtr!` for every field in component bodies.
+///
+/// Each field is localized as the message named after it in kebab case (e.g. `first_name`
+/// as `first-name`), which can be overridden with a `#[msg("message-id")]` attribute.
+/// The returned struct is named after the struct with a `Messages` suffix and has the same
+/// visibility, as do its fields.
+///
+/// The messages are queried from the `LOCALES` static in scope of the struct, or the one
+/// given with a `#[locales(VAR_NAME)]` attribute on the struct. Like `rtr!`, `localize()`
+/// must be called within a reactive owner that has the `LangIdContext`.
+///
+/// ## Example
+/// ```ignore
+/// #[derive(Localize)]
+/// struct SignupForm {
+///     #[msg("signup-email-label")]
+///     email: String,
+///     first_name: String,
+/// }
+///
+/// let labels = SignupForm::localize();
+/// view! { <label>{move || labels.email.value()}</label> }
+/// ```
+#[proc_macro_derive(Localize, attributes(msg, locales))]
+pub fn derive_localize(input: TokenStream) -> TokenStream {
+    localize::derive_localize_impl(input)
+}
@@ -0,0 +1,73 @@
+use crate::localized::find_lit_attr;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, LitStr};
+
+pub fn derive_localize_impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    match expand(&input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Localize can only be derived for structs.",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Localize can only be derived for structs with named fields.",
+        ));
+    };
+
+    let mut locales_var = Ident::new("LOCALES", Span::call_site());
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("locales"))
+    {
+        locales_var = attr.parse_args()?;
+    }
+
+    let mut message_fields = Vec::new();
+    let mut messages = Vec::new();
+    for field in &fields.named {
+        let Some(ident) = &field.ident else { continue };
+        let id = find_lit_attr(&field.attrs, "msg")?
+            .unwrap_or_else(|| LitStr::new(&ident.to_string().replace('_', "-"), ident.span()));
+        let vis = &field.vis;
+        message_fields.push(quote! { #vis #ident: i18n_leptos::ReactiveMessage });
+        messages.push(quote! { #ident: i18n_leptos::rtr!(#id, "locales" = #locales_var) });
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let messages_name = format_ident!("{name}Messages");
+    let doc = format!("The localized messages of the fields of `{name}`, see `{name}::localize`.");
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #messages_name {
+            #(#message_fields,)*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Creates a reactive message for each field, as with `rtr!`.
+            #vis fn localize() -> #messages_name {
+                #messages_name {
+                    #(#messages,)*
+                }
+            }
+        }
+    })
+}
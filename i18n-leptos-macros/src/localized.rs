@@ -0,0 +1,99 @@
+use crate::selector::to_kebab_case;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, LitStr};
+
+/// Returns the string literal of the last `#[name("...")]` attribute in `attrs`, if any.
+pub(crate) fn find_lit_attr(attrs: &[Attribute], name: &str) -> syn::Result<Option<LitStr>> {
+    let mut lit = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        lit = Some(attr.parse_args::<LitStr>()?);
+    }
+    Ok(lit)
+}
+
+pub fn derive_localized_display_impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    match expand(&input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "LocalizedDisplay can only be derived for enums.",
+        ));
+    };
+
+    let mut locales_var = Ident::new("LOCALES", Span::call_site());
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("locales"))
+    {
+        locales_var = attr.parse_args()?;
+    }
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let id = find_lit_attr(&variant.attrs, "msg")?.unwrap_or_else(|| {
+            LitStr::new(
+                &to_kebab_case(&variant_ident.to_string()),
+                variant_ident.span(),
+            )
+        });
+
+        // every field is passed as an argument, named after the field unless renamed
+        let mut bindings = Vec::new();
+        let mut args = Vec::new();
+        for (i, field) in variant.fields.iter().enumerate() {
+            let binding = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => format_ident!("field_{i}"),
+            };
+            let name = match (find_lit_attr(&field.attrs, "arg")?, &field.ident) {
+                (Some(name), _) => name,
+                (None, Some(ident)) => LitStr::new(&ident.to_string(), ident.span()),
+                (None, None) => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "Tuple fields need an `#[arg(\"name\")]` attribute naming their argument.",
+                    ));
+                }
+            };
+            args.push(quote! { .with_arg(#name, ::std::clone::Clone::clone(#binding)) });
+            bindings.push(binding);
+        }
+
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote! { Self::#variant_ident { #(#bindings),* } },
+            Fields::Unnamed(_) => quote! { Self::#variant_ident(#(#bindings),*) },
+            Fields::Unit => quote! { Self::#variant_ident },
+        };
+        arms.push(quote! {
+            #pattern => (#id, i18n::Query::new(#id) #(#args)*),
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics i18n::LocalizedDisplay for #name #ty_generics #where_clause {
+            fn localize(&self, langid: &i18n::LanguageIdentifier) -> i18n::Message {
+                let (id, query) = match self {
+                    #(#arms)*
+                };
+                i18n_leptos::__private::query(&#locales_var, langid, id, &query)
+                    .unwrap_or_else(|errs| i18n_leptos::__private::fallback_message(id, langid, errs))
+            }
+        }
+    })
+}
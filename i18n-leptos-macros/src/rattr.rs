@@ -1,12 +1,15 @@
+use crate::rtr::{check_duplicate_arg, check_duplicate_setting, check_implicit_arg};
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
 use syn::{Expr, LitStr, Token};
 
 struct RattrMacroInput {
     msg: Expr,
     attr: LitStr,
     args: Vec<(LitStr, Expr)>,
+    default: Option<Expr>,
 }
 
 impl Parse for RattrMacroInput {
@@ -16,6 +19,11 @@ impl Parse for RattrMacroInput {
         let attr: LitStr = input.parse()?;
 
         let mut args = Vec::new();
+        let mut default = None;
+        let mut plural: Option<Expr> = None;
+        let mut plural_arg = None;
+        // the special keys passed so far, along with the setting they set
+        let mut settings: Vec<(&'static str, LitStr)> = Vec::new();
         while input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
             if input.is_empty() {
@@ -23,32 +31,85 @@ impl Parse for RattrMacroInput {
             }
             let key: LitStr = input.parse()?;
             input.parse::<Token![=]>()?;
-            let value: Expr = input.parse()?;
-            args.push((key, value));
+            // like in `rtr!`, settings and arguments may only be passed once
+            let setting = ["default", "plural", "plural_arg"]
+                .into_iter()
+                .find(|setting| key.value() == *setting);
+            match setting {
+                Some(setting) => {
+                    check_duplicate_setting(setting, &key, &settings)?;
+                    settings.push((setting, key.clone()));
+                }
+                None => check_duplicate_arg(&key, args.iter())?,
+            }
+            match key.value().as_str() {
+                "default" => default = Some(input.parse()?),
+                "plural" => plural = Some(input.parse()?),
+                "plural_arg" => plural_arg = Some(input.parse::<LitStr>()?),
+                _ => args.push((key, input.parse()?)),
+            }
         }
 
-        Ok(RattrMacroInput { msg, attr, args })
+        if plural.is_none()
+            && let Some((_, key)) = settings
+                .iter()
+                .find(|(setting, _)| *setting == "plural_arg")
+        {
+            return Err(syn::Error::new_spanned(
+                key,
+                "'plural_arg' is passed without 'plural'.",
+            ));
+        }
+
+        // like in `rtr!`, the number is passed as a numeric value selecting the plural category
+        if let Some(value) = plural {
+            // the default name is spanned to the value, so that collisions point at it
+            let name = plural_arg.unwrap_or_else(|| LitStr::new("count", value.span()));
+            check_implicit_arg("plural", &name, args.iter())?;
+            let value = syn::parse_quote_spanned! {value.span()=>
+                i18n_leptos::__private::plural(#value)
+            };
+            args.push((name, value));
+        }
+
+        Ok(RattrMacroInput {
+            msg,
+            attr,
+            args,
+            default,
+        })
     }
 }
 
 pub fn rattr_impl(input: TokenStream) -> TokenStream {
-    let RattrMacroInput { msg, attr, args } = match syn::parse(input) {
+    let RattrMacroInput {
+        msg,
+        attr,
+        args,
+        default,
+    } = match syn::parse(input) {
         Ok(input) => input,
         Err(err) => return err.to_compile_error().into(),
     };
+    let default = match default {
+        Some(default) => {
+            quote_spanned! {default.span()=> ::std::convert::AsRef::<str>::as_ref(&(#default)) }
+        }
+        None => quote! { #attr },
+    };
 
     if args.is_empty() {
-        TokenStream::from(quote! { #msg.attr(#attr, None) })
+        TokenStream::from(quote! { #msg.attr_or(#attr, None, #default) })
     } else {
         let mut fluent_args = quote! { let mut args = i18n::FluentArgs::new(); };
         for (key, value) in args {
-            fluent_args.extend(quote! { args.set(#key, #value); });
+            fluent_args.extend(quote_spanned! {value.span()=> args.set(#key, #value); });
         }
 
         TokenStream::from(quote! {
             {
                 #fluent_args
-                #msg.attr(#attr, Some(&args))
+                #msg.attr_or(#attr, Some(&args), #default)
             }
         })
     }
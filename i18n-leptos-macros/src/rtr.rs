@@ -1,8 +1,10 @@
+use crate::{ftl, usage};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::collections::HashMap;
 use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
 use syn::{Expr, Ident, LitStr, Token};
 
 enum RtrInputKind {
@@ -12,69 +14,249 @@ enum RtrInputKind {
 
 enum RtrArg {
     Locales(Ident),
+    Args(Expr),
+    Plural(Expr),
+    PluralArg(LitStr),
+    Ordinal(Expr),
+    OrdinalArg(LitStr),
+    GetAttr(LitStr),
+    Default(Expr),
+    LangId(Expr),
+    Select {
+        key: LitStr,
+        value: Expr,
+    },
     Main {
         key: LitStr,
         value: Expr,
     },
     Attribute {
         attr: LitStr,
-        key: LitStr,
-        value: Expr,
+        args: Vec<(LitStr, Expr)>,
     },
 }
 
+impl RtrArg {
+    /// Returns the name of the setting this special key sets, shared by keys that set
+    /// the same one (like `plural` and `plural_signal`), or `None` for message arguments,
+    /// which may be passed any number of times.
+    fn setting(&self) -> Option<&'static str> {
+        match self {
+            RtrArg::Locales(_) => Some("locales"),
+            RtrArg::Args(_) => Some("args"),
+            RtrArg::Plural(_) => Some("plural"),
+            RtrArg::PluralArg(_) => Some("plural_arg"),
+            RtrArg::Ordinal(_) => Some("ordinal"),
+            RtrArg::OrdinalArg(_) => Some("ordinal_arg"),
+            RtrArg::GetAttr(_) => Some("get_attr"),
+            RtrArg::Default(_) => Some("default"),
+            RtrArg::LangId(_) => Some("langid"),
+            RtrArg::Select { .. } | RtrArg::Main { .. } | RtrArg::Attribute { .. } => None,
+        }
+    }
+}
+
 struct RtrArgs {
     locales_var: Ident,
+    runtime_args: Option<Expr>,
+    plural: Option<(LitStr, Expr)>,
+    ordinal: Option<(LitStr, Expr)>,
+    get_attr: Option<LitStr>,
+    default: Option<Expr>,
+    langid: Option<Expr>,
+    select_args: Vec<(LitStr, Expr)>,
     main_args: Vec<(LitStr, Expr)>,
     attr_args: HashMap<String, Vec<(LitStr, Expr)>>,
+    /// Every attribute name passed to `attr(...)`, kept for diagnostics.
+    attr_names: Vec<LitStr>,
 }
 
 impl Parse for RtrArgs {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut locales_var = Ident::new("LOCALES", Span::call_site());
+        let mut runtime_args = None;
+        let mut plural = None;
+        let mut plural_arg = None;
+        let mut ordinal = None;
+        let mut ordinal_arg = None;
+        let mut get_attr = None;
+        let mut default = None;
+        let mut langid = None;
+        let mut select_args = Vec::new();
         let mut main_args = Vec::new();
         let mut attr_args: HashMap<String, Vec<(LitStr, Expr)>> = HashMap::new();
+        let mut attr_names = Vec::new();
+        // the special keys passed so far, along with the setting they set
+        let mut settings: Vec<(&'static str, LitStr)> = Vec::new();
 
+        // arguments are independent of each other, so they may come in any order
         while !input.is_empty() {
             input.parse::<Token![,]>()?;
             if input.is_empty() {
                 break;
             }
 
+            let key = if input.peek(LitStr) {
+                Some(input.fork().parse::<LitStr>()?)
+            } else {
+                None
+            };
             let arg = input.parse::<RtrArg>()?;
+            if let (Some(setting), Some(key)) = (arg.setting(), key) {
+                check_duplicate_setting(setting, &key, &settings)?;
+                settings.push((setting, key));
+            }
             match arg {
                 RtrArg::Locales(ident) => locales_var = ident,
-                RtrArg::Main { key, value } => main_args.push((key, value)),
-                RtrArg::Attribute { attr, key, value } => {
-                    attr_args
-                        .entry(attr.value())
-                        .or_default()
-                        .push((key, value));
+                RtrArg::Args(value) => runtime_args = Some(value),
+                RtrArg::Plural(value) => plural = Some(value),
+                RtrArg::PluralArg(name) => plural_arg = Some(name),
+                RtrArg::Ordinal(value) => ordinal = Some(value),
+                RtrArg::OrdinalArg(name) => ordinal_arg = Some(name),
+                RtrArg::GetAttr(attr) => get_attr = Some(attr),
+                RtrArg::Default(value) => default = Some(value),
+                RtrArg::LangId(value) => langid = Some(value),
+                RtrArg::Select { key, value } => {
+                    check_duplicate_arg(&key, select_args.iter().chain(&main_args))?;
+                    select_args.push((key, value));
+                }
+                RtrArg::Main { key, value } => {
+                    check_duplicate_arg(&key, select_args.iter().chain(&main_args))?;
+                    main_args.push((key, value));
+                }
+                RtrArg::Attribute { attr, args } => {
+                    attr_names.push(attr.clone());
+                    let attr_args = attr_args.entry(attr.value()).or_default();
+                    for (key, value) in args {
+                        check_duplicate_arg(&key, attr_args.iter())?;
+                        attr_args.push((key, value));
+                    }
                 }
             }
         }
 
+        // default names are spanned to the value, so that collisions point at it
+        let plural = plural.map(|value: Expr| {
+            let name = plural_arg.unwrap_or_else(|| LitStr::new("count", value.span()));
+            (name, value)
+        });
+        let ordinal = ordinal.map(|value: Expr| {
+            let name = ordinal_arg.unwrap_or_else(|| LitStr::new("place", value.span()));
+            (name, value)
+        });
+        if let Some((name, _)) = &plural {
+            check_implicit_arg("plural", name, select_args.iter().chain(&main_args))?;
+        }
+        if let Some((name, _)) = &ordinal {
+            let args = select_args.iter().chain(&main_args).chain(&plural);
+            check_implicit_arg("ordinal", name, args)?;
+        }
+
         Ok(RtrArgs {
             locales_var,
+            runtime_args,
+            plural,
+            ordinal,
+            get_attr,
+            default,
+            langid,
+            select_args,
             main_args,
             attr_args,
+            attr_names,
         })
     }
 }
 
+/// Fails if an argument named like `key` was already passed in `args`, as only the last
+/// one would take effect.
+pub(crate) fn check_duplicate_arg<'a>(
+    key: &LitStr,
+    mut args: impl Iterator<Item = &'a (LitStr, Expr)>,
+) -> Result<()> {
+    if args.any(|(other, _)| other.value() == key.value()) {
+        return Err(syn::Error::new_spanned(
+            key,
+            format!("Argument '{}' is passed more than once.", key.value()),
+        ));
+    }
+    Ok(())
+}
+
+/// Fails if the special key `key` sets a `setting` that was already set by one of `settings`,
+/// e.g. by passing `default` twice or both `plural` and `plural_signal`.
+pub(crate) fn check_duplicate_setting(
+    setting: &str,
+    key: &LitStr,
+    settings: &[(&'static str, LitStr)],
+) -> Result<()> {
+    match settings.iter().find(|(other, _)| *other == setting) {
+        Some((_, other)) if other.value() == key.value() => Err(syn::Error::new_spanned(
+            key,
+            format!("'{}' is passed more than once.", key.value()),
+        )),
+        Some((_, other)) => Err(syn::Error::new_spanned(
+            key,
+            format!(
+                "'{}' can't be combined with '{}'.",
+                key.value(),
+                other.value()
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Fails if the argument `name` set by the special key `kind` (e.g. `count` for `plural`)
+/// is also passed in `args`, as only one of them would take effect.
+pub(crate) fn check_implicit_arg<'a>(
+    kind: &str,
+    name: &LitStr,
+    mut args: impl Iterator<Item = &'a (LitStr, Expr)>,
+) -> Result<()> {
+    if args.any(|(other, _)| other.value() == name.value()) {
+        return Err(syn::Error::new_spanned(
+            name,
+            format!(
+                "Argument '{}' set by `{kind}` is passed more than once, rename it with `{kind}_arg`.",
+                name.value()
+            ),
+        ));
+    }
+    Ok(())
+}
+
 impl Parse for RtrArg {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(LitStr) && input.peek2(Token![=]) {
             let key: LitStr = input.parse()?;
             input.parse::<Token![=]>()?;
-            if key.value() == "locales" {
-                Ok(RtrArg::Locales(input.parse()?))
-            } else {
-                Ok(RtrArg::Main {
+            match key.value().as_str() {
+                "locales" => Ok(RtrArg::Locales(input.parse()?)),
+                "args" => Ok(RtrArg::Args(input.parse()?)),
+                "plural" => Ok(RtrArg::Plural(input.parse()?)),
+                "plural_signal" => {
+                    // read the signal within the message's memo, so that it is re-resolved
+                    let signal: Expr = input.parse()?;
+                    Ok(RtrArg::Plural(syn::parse_quote_spanned! {signal.span()=>
+                        leptos::prelude::Get::get(&(#signal))
+                    }))
+                }
+                "plural_arg" => Ok(RtrArg::PluralArg(input.parse()?)),
+                "ordinal" => Ok(RtrArg::Ordinal(input.parse()?)),
+                "ordinal_arg" => Ok(RtrArg::OrdinalArg(input.parse()?)),
+                "get_attr" => Ok(RtrArg::GetAttr(input.parse()?)),
+                "default" => Ok(RtrArg::Default(input.parse()?)),
+                "langid" => Ok(RtrArg::LangId(input.parse()?)),
+                name if name.starts_with("select_") => Ok(RtrArg::Select {
+                    key: LitStr::new(&name["select_".len()..], key.span()),
+                    value: input.parse()?,
+                }),
+                _ => Ok(RtrArg::Main {
                     key,
                     value: input.parse()?,
-                })
+                }),
             }
         } else if lookahead.peek(Ident) && input.peek2(syn::token::Paren) {
             let attr_ident: Ident = input.parse()?; // Parse 'attr'
@@ -88,15 +270,27 @@ impl Parse for RtrArg {
             syn::parenthesized!(content in input); // Parse content within parentheses
 
             let attr_id: LitStr = content.parse()?;
-            content.parse::<Token![,]>()?;
-            let arg_key: LitStr = content.parse()?;
-            content.parse::<Token![=]>()?;
-            let arg_value: Expr = content.parse()?;
+            // any number of arguments may follow, e.g. `attr("title", "a" = 1, "b" = 2)`
+            let mut args = Vec::new();
+            while !content.is_empty() {
+                content.parse::<Token![,]>()?;
+                if content.is_empty() {
+                    break;
+                }
+                let arg_key: LitStr = content.parse()?;
+                content.parse::<Token![=]>()?;
+                args.push((arg_key, content.parse()?));
+            }
+            if args.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    attr_id,
+                    "Expected at least one `key = value` argument for the attribute.",
+                ));
+            }
 
             Ok(RtrArg::Attribute {
                 attr: attr_id,
-                key: arg_key,
-                value: arg_value,
+                args,
             })
         } else {
             Err(lookahead.error())
@@ -125,30 +319,99 @@ impl Parse for RtrMacroInput {
 }
 
 pub fn rtr_impl(input: TokenStream) -> TokenStream {
-    let RtrMacroInput { kind, args } = match syn::parse(input) {
+    let RtrMacroInput { kind, mut args } = match syn::parse(input) {
         Ok(input) => input,
         Err(err) => return err.to_compile_error().into(),
     };
+    let get_attr = args.get_attr.take();
 
-    match kind {
+    let message = match kind {
         RtrInputKind::MessageId(id) => {
+            let index = match ftl::default_locale_index().transpose() {
+                Ok(index) => index,
+                Err(err) => return syn::Error::new(id.span(), err).to_compile_error().into(),
+            };
+            let index = index.as_deref();
+            let attrs = args.attr_names.iter().chain(&get_attr);
+            if let Err(err) = validate_against_ftl(index, &id, attrs.clone()) {
+                return err.to_compile_error().into();
+            }
+            if let Err(err) = check_argument_types(index, &id, &args) {
+                return err.to_compile_error().into();
+            }
+            let mut warnings = selector_warnings(index, &id, &args);
+            // the report is a side channel, so failing to write it mustn't fail the build
+            if let Err(err) = usage::record_usage(&id, attrs) {
+                warnings.extend(warning(id.span(), &err));
+            }
+            if let Some(index) = index {
+                warnings.extend(ftl::track_files(index));
+            }
+
             let RtrArgs {
                 locales_var,
+                runtime_args,
+                plural,
+                ordinal,
+                default,
+                langid,
+                select_args,
                 main_args,
                 attr_args,
+                ..
             } = args;
-            let mut query_builder = quote! { i18n::Query::new(#id) };
+            // messages without arguments are resolved once per language and shared
+            if runtime_args.is_none()
+                && default.is_none()
+                && langid.is_none()
+                && plural.is_none()
+                && ordinal.is_none()
+                && select_args.is_empty()
+                && main_args.is_empty()
+                && attr_args.is_empty()
+            {
+                return wrap_get_attr(
+                    quote! { i18n_leptos::__private::reactive_cached(&#locales_var, #id) },
+                    get_attr,
+                );
+            }
+
+            // runtime args go first so that inline args override them on conflict
+            let mut query_builder = match runtime_args {
+                Some(runtime_args) => quote_spanned! {runtime_args.span()=>
+                    i18n_leptos::__private::with_args(i18n::Query::new(#id), &(#runtime_args))
+                },
+                None => quote! { i18n::Query::new(#id) },
+            };
+
+            // argument calls are spanned to the user's expressions, so that type errors
+            // point at the offending expression instead of the macro invocation
+            if let Some((name, value)) = plural {
+                query_builder.extend(quote_spanned! {value.span()=>
+                    .with_arg(#name, i18n_leptos::__private::plural(#value))
+                });
+            }
+            if let Some((name, value)) = ordinal {
+                query_builder.extend(quote_spanned! {value.span()=>
+                    .with_arg(#name, i18n_leptos::__private::ordinal(#value))
+                });
+            }
+            for (key, value) in select_args {
+                query_builder.extend(quote_spanned! {value.span()=>
+                    .with_arg(#key, i18n_leptos::__private::select(&(#value)))
+                });
+            }
 
             let main_args_tokens: Vec<_> = main_args
                 .into_iter()
-                .map(|(key, value)| quote! { .with_arg(#key, #value) })
+                .map(|(key, value)| quote_spanned! {value.span()=> .with_arg(#key, i18n_leptos::__private::arg(#value)) })
                 .collect();
 
             let attr_args_tokens: Vec<_> = attr_args
                 .into_iter()
                 .flat_map(|(attr_name, args)| {
                     args.into_iter().map(move |(key, value)| {
-                        quote! { .with_attr_arg(#attr_name, #key, #value) }
+                        quote_spanned! {value.span()=> .with_attr_arg(#attr_name, #key, i18n_leptos::__private::arg(#value)) }
                     })
                 })
                 .collect();
@@ -156,43 +419,80 @@ pub fn rtr_impl(input: TokenStream) -> TokenStream {
             query_builder.extend(main_args_tokens);
             query_builder.extend(attr_args_tokens);
 
-            let query_call_block = quote! {
-                #locales_var.query(&langid.get(), &#query_builder)
+            let query = quote! {
+                move |langid| i18n_leptos::__private::query(&#locales_var, langid, #id, &#query_builder)
             };
-
-            let final_expansion = quote! {
-                {
-                    let msg = leptos::prelude::RwSignal::default();
-
-                    leptos::prelude::Effect::new(move || {
-                        let langid = i18n_leptos::expect_langid();
-                        msg.set(#query_call_block.unwrap_or_else(|_errs| {
-                            i18n::Message {
-                                id: #id.to_string(),
-                                value: #id.to_string(),
-                                attrs: Default::default(),
-                            }
-                        }));
-                    });
-
-                    i18n_leptos::ReactiveMessage::new(msg)
-                }
+            let message = if default.is_none() && langid.is_none() {
+                quote! { i18n_leptos::__private::reactive_query(#id, #query) }
+            } else {
+                let default = match default {
+                    Some(default) => quote_spanned! {default.span()=>
+                        ::std::option::Option::Some((#default).into())
+                    },
+                    None => quote! { ::std::option::Option::None },
+                };
+                let langid = match langid {
+                    Some(langid) => quote_spanned! {langid.span()=>
+                        ::std::option::Option::Some((#langid).into())
+                    },
+                    None => quote! { ::std::option::Option::None },
+                };
+                quote! { i18n_leptos::__private::reactive_query_with(#id, #default, #langid, #query) }
             };
-            TokenStream::from(final_expansion)
+            if warnings.is_empty() {
+                message
+            } else {
+                quote! { { #warnings #message } }
+            }
         }
         RtrInputKind::LocalizedDisplayExpr(expr) => {
-            if !args.main_args.is_empty() || !args.attr_args.is_empty() {
+            if args.runtime_args.is_some()
+                || args.default.is_some()
+                || args.langid.is_some()
+                || args.plural.is_some()
+                || args.ordinal.is_some()
+                || !args.select_args.is_empty()
+                || !args.attr_args.is_empty()
+            {
                 let mut error = syn::Error::new_spanned(
                     expr,
-                    "Arguments are not supported when passing a LocalizedDisplay object.",
+                    "Only `key = value` arguments are supported when passing a LocalizedDisplay object.",
                 );
-                if !args.main_args.is_empty() {
-                    for (key, _) in args.main_args {
-                        error.combine(syn::Error::new_spanned(
-                            key,
-                            "Main arguments are not supported here.",
-                        ));
-                    }
+                if let Some(runtime_args) = args.runtime_args {
+                    error.combine(syn::Error::new_spanned(
+                        runtime_args,
+                        "Runtime arguments are not supported here.",
+                    ));
+                }
+                if let Some(default) = args.default {
+                    error.combine(syn::Error::new_spanned(
+                        default,
+                        "Default values are not supported here.",
+                    ));
+                }
+                if let Some(langid) = args.langid {
+                    error.combine(syn::Error::new_spanned(
+                        langid,
+                        "Langid overrides are not supported here.",
+                    ));
+                }
+                if let Some((_, value)) = args.plural {
+                    error.combine(syn::Error::new_spanned(
+                        value,
+                        "Plural arguments are not supported here.",
+                    ));
+                }
+                if let Some((_, value)) = args.ordinal {
+                    error.combine(syn::Error::new_spanned(
+                        value,
+                        "Ordinal arguments are not supported here.",
+                    ));
+                }
+                for (_, value) in args.select_args {
+                    error.combine(syn::Error::new_spanned(
+                        value,
+                        "Select arguments are not supported here.",
+                    ));
                 }
                 if !args.attr_args.is_empty() {
                     for (attr, args) in args.attr_args {
@@ -209,7 +509,321 @@ pub fn rtr_impl(input: TokenStream) -> TokenStream {
                 }
                 return error.to_compile_error().into();
             }
-            TokenStream::from(quote! { #expr.reactive_localize() })
+            if args.main_args.is_empty() {
+                quote! { #expr.reactive_localize() }
+            } else {
+                let mut fluent_args = quote! { let mut args = i18n::FluentArgs::new(); };
+                for (key, value) in args.main_args {
+                    fluent_args.extend(quote_spanned! {value.span()=> args.set(#key, #value); });
+                }
+                quote! {
+                    i18n_leptos::__private::reactive_localize_with_args(#expr, move || {
+                        #fluent_args
+                        args
+                    })
+                }
+            }
+        }
+    };
+
+    wrap_get_attr(message, get_attr)
+}
+
+/// Wraps the expansion of a message into a signal of its attribute `get_attr`, if given.
+fn wrap_get_attr(message: proc_macro2::TokenStream, get_attr: Option<LitStr>) -> TokenStream {
+    match get_attr {
+        Some(attr) => TokenStream::from(quote! {
+            {
+                let msg = #message;
+                leptos::prelude::Signal::derive(move || msg.attr(#attr, None))
+            }
+        }),
+        None => TokenStream::from(message),
+    }
+}
+
+/// Returns warnings for the `plural`, `ordinal` and `select_*` arguments of an `rtr!` call
+/// whose message doesn't select on them in the default locale's FTL files, if
+/// `I18N_LEPTOS_FTL_DIR` is set, as the argument then can't pick a variant.
+fn selector_warnings(
+    index: Option<&ftl::FtlIndex>,
+    id: &LitStr,
+    args: &RtrArgs,
+) -> proc_macro2::TokenStream {
+    let Some(index) = index else {
+        return proc_macro2::TokenStream::new();
+    };
+    // unknown messages are already reported by `validate_against_ftl`
+    let Some(message) = index.messages.get(&id.value()) else {
+        return proc_macro2::TokenStream::new();
+    };
+
+    let selector_args = args.plural.iter().map(|arg| ("plural", arg));
+    let selector_args = selector_args.chain(args.ordinal.iter().map(|arg| ("ordinal", arg)));
+    let selector_args = selector_args.chain(args.select_args.iter().map(|arg| ("select", arg)));
+    selector_args
+        .filter(|(_, (name, _))| !message.selectors.contains(&name.value()))
+        .map(|(kind, (name, value))| {
+            let note = if message.selectors.is_empty() {
+                format!(
+                    "Message '{}' has no selector, so the `{kind}` argument `${}` doesn't pick a variant.",
+                    id.value(),
+                    name.value(),
+                )
+            } else {
+                format!(
+                    "Message '{}' doesn't select on `${}`, so the `{kind}` argument doesn't pick a variant.",
+                    id.value(),
+                    name.value(),
+                )
+            };
+            warning(value.span(), &note)
+        })
+        .collect()
+}
+
+/// Emits `note` as a warning at `span`, through the deprecation lint,
+/// as proc macros can't emit warnings on stable.
+fn warning(span: Span, note: &str) -> proc_macro2::TokenStream {
+    quote_spanned! {span=>
+        {
+            #[allow(non_camel_case_types)]
+            #[deprecated(note = #note)]
+            struct rtr_warning;
+            let _ = rtr_warning;
+        }
+    }
+}
+
+/// Fails if a string literal is passed for an argument that the message `id` selects
+/// a plural category or number on, as strings never select those variants, e.g.
+/// `"count" = "5"` for `{ $count -> [one] ... *[other] ... }`, if `I18N_LEPTOS_FTL_DIR` is set.
+fn check_argument_types(index: Option<&ftl::FtlIndex>, id: &LitStr, args: &RtrArgs) -> Result<()> {
+    let Some(index) = index else {
+        return Ok(());
+    };
+    let Some(message) = index.messages.get(&id.value()) else {
+        return Ok(());
+    };
+
+    let mut error: Option<syn::Error> = None;
+    for (name, value) in &args.main_args {
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(_),
+            ..
+        }) = value
+        else {
+            continue;
+        };
+        if !message.numeric_selectors.contains(&name.value()) {
+            continue;
+        }
+        let arg_error = syn::Error::new_spanned(
+            value,
+            format!(
+                "Argument '{}' of message '{}' is a string, but the message selects a plural \
+                 category on it, which needs a number. Pass a number, e.g. with `plural = n`.",
+                name.value(),
+                id.value(),
+            ),
+        );
+        match &mut error {
+            Some(error) => error.combine(arg_error),
+            None => error = Some(arg_error),
+        }
+    }
+    error.map_or(Ok(()), Err)
+}
+
+/// Checks that the message and attributes referenced by an `rtr!` call exist in the
+/// default locale's FTL files, if `I18N_LEPTOS_FTL_DIR` is set.
+fn validate_against_ftl<'a>(
+    index: Option<&ftl::FtlIndex>,
+    id: &LitStr,
+    attrs: impl Iterator<Item = &'a LitStr>,
+) -> Result<()> {
+    let Some(index) = index else {
+        return Ok(());
+    };
+
+    let Some(message) = index.messages.get(&id.value()) else {
+        return Err(syn::Error::new_spanned(
+            id,
+            format!(
+                "Message '{}' not found in the FTL files of `{}`.",
+                id.value(),
+                ftl::FTL_DIR_ENV
+            ),
+        ));
+    };
+
+    let mut error: Option<syn::Error> = None;
+    if let Some(cycle) = index.find_cycle(&id.value()) {
+        error = Some(syn::Error::new_spanned(
+            id,
+            format!(
+                "Message '{}' can't be resolved, as it depends on the cyclic message references {}.",
+                id.value(),
+                cycle.join(" -> ")
+            ),
+        ));
+    }
+    for attr in attrs.filter(|attr| !message.attrs.contains(&attr.value())) {
+        let attr_error = syn::Error::new_spanned(
+            attr,
+            format!(
+                "Attribute '{}' of message '{}' not found in the FTL files of `{}`.",
+                attr.value(),
+                id.value(),
+                ftl::FTL_DIR_ENV
+            ),
+        );
+        match &mut error {
+            Some(error) => error.combine(attr_error),
+            None => error = Some(attr_error),
+        }
+    }
+    error.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn tokens(value: &impl ToTokens) -> String {
+        value.to_token_stream().to_string()
+    }
+
+    fn arg_pairs(args: &[(LitStr, Expr)]) -> Vec<(String, String)> {
+        let mut pairs: Vec<_> = args
+            .iter()
+            .map(|(key, value)| (key.value(), tokens(value)))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Parses `rtr!` arguments into a description that doesn't depend on their order.
+    fn describe(input: &str) -> Result<String> {
+        let RtrMacroInput { args, .. } = syn::parse_str(input)?;
+        let named = |arg: &Option<(LitStr, Expr)>| {
+            arg.as_ref()
+                .map(|(name, value)| (name.value(), tokens(value)))
+        };
+        let mut attr_args: Vec<_> = args
+            .attr_args
+            .iter()
+            .map(|(attr, args)| (attr.clone(), arg_pairs(args)))
+            .collect();
+        attr_args.sort();
+        Ok(format!(
+            "{} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+            args.locales_var,
+            args.runtime_args.as_ref().map(|value| tokens(value)),
+            named(&args.plural),
+            named(&args.ordinal),
+            args.get_attr.as_ref().map(|attr| attr.value()),
+            args.default.as_ref().map(|value| tokens(value)),
+            args.langid.as_ref().map(|value| tokens(value)),
+            arg_pairs(&args.select_args),
+            arg_pairs(&args.main_args),
+            attr_args,
+        ))
+    }
+
+    /// Calls `f` with every ordering of `items[k..]`.
+    fn permutations(items: &mut [&'static str], k: usize, f: &mut impl FnMut(&[&'static str])) {
+        if k == items.len() {
+            f(items);
+            return;
+        }
+        for i in k..items.len() {
+            items.swap(k, i);
+            permutations(items, k + 1, f);
+            items.swap(k, i);
         }
     }
+
+    fn input(items: &[&str]) -> String {
+        format!("\"id\", {}", items.join(", "))
+    }
+
+    #[test]
+    fn arguments_parse_the_same_in_any_order() {
+        let mut items = [
+            "\"locales\" = APP_LOCALES",
+            "\"args\" = extra",
+            "\"default\" = \"Hi\"",
+            "\"plural\" = n",
+            "\"ordinal\" = rank",
+            "\"name\" = user",
+            "\"select_gender\" = gender",
+            "attr(\"title\", \"who\" = user, \"when\" = time)",
+        ];
+        let expected = describe(&input(&items)).unwrap();
+        permutations(&mut items, 0, &mut |items| {
+            let input = input(items);
+            assert_eq!(describe(&input).unwrap(), expected, "{input}");
+        });
+    }
+
+    #[test]
+    fn settings_parse_the_same_in_any_order() {
+        let mut items = [
+            "\"langid\" = lang",
+            "\"get_attr\" = \"title\"",
+            "\"plural_signal\" = count",
+            "\"plural_arg\" = \"n\"",
+            "\"ordinal_arg\" = \"pos\"",
+            "\"ordinal\" = rank",
+        ];
+        let expected = describe(&input(&items)).unwrap();
+        permutations(&mut items, 0, &mut |items| {
+            let input = input(items);
+            assert_eq!(describe(&input).unwrap(), expected, "{input}");
+        });
+    }
+
+    #[test]
+    fn attribute_arguments_dont_need_main_arguments() {
+        let expected =
+            describe(r#""id", attr("title", "who" = user), attr("title", "when" = time)"#);
+        let merged = describe(r#""id", attr("title", "when" = time, "who" = user)"#);
+        assert_eq!(expected.unwrap(), merged.unwrap());
+    }
+
+    /// Asserts that every ordering of `items` fails to parse.
+    fn assert_rejected_in_any_order(mut items: Vec<&'static str>) {
+        permutations(&mut items, 0, &mut |items| {
+            let input = input(items);
+            assert!(describe(&input).is_err(), "{input}");
+        });
+    }
+
+    #[test]
+    fn duplicates_are_rejected_in_any_order() {
+        assert_rejected_in_any_order(vec!["\"name\" = a", "\"name\" = b", "\"default\" = d"]);
+        assert_rejected_in_any_order(vec!["\"default\" = a", "\"default\" = b", "\"n\" = n"]);
+        assert_rejected_in_any_order(vec!["\"plural\" = a", "\"plural_signal\" = b"]);
+        assert_rejected_in_any_order(vec!["\"plural\" = n", "\"count\" = c", "\"name\" = a"]);
+        assert_rejected_in_any_order(vec!["\"ordinal\" = n", "\"place\" = c", "\"name\" = a"]);
+        assert_rejected_in_any_order(vec!["\"plural\" = n", "\"select_count\" = c"]);
+        assert_rejected_in_any_order(vec![
+            "\"plural\" = n",
+            "\"ordinal\" = r",
+            "\"ordinal_arg\" = \"count\"",
+        ]);
+        assert_rejected_in_any_order(vec![
+            "attr(\"title\", \"who\" = a)",
+            "attr(\"title\", \"who\" = b)",
+            "\"name\" = c",
+        ]);
+    }
+
+    #[test]
+    fn distinct_implicit_names_are_accepted() {
+        let parsed = describe(r#""id", "plural" = n, "ordinal" = r, "name" = a"#);
+        assert!(parsed.is_ok());
+    }
 }
@@ -0,0 +1,73 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Converts a variant name like `NonBinary` into `non-binary`.
+pub(crate) fn to_kebab_case(name: &str) -> String {
+    let mut kebab = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            kebab.push('-');
+        }
+        kebab.extend(c.to_lowercase());
+    }
+    kebab
+}
+
+pub fn derive_fluent_selector_impl(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let Data::Enum(data) = &input.data else {
+        return syn::Error::new_spanned(
+            &input.ident,
+            "FluentSelector can only be derived for enums.",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return syn::Error::new_spanned(
+                variant,
+                "FluentSelector can only be derived for enums of unit variants.",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let mut key = LitStr::new(
+            &to_kebab_case(&variant.ident.to_string()),
+            variant.ident.span(),
+        );
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("selector"))
+        {
+            match attr.parse_args::<LitStr>() {
+                Ok(custom_key) => key = custom_key,
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
+
+        let variant = &variant.ident;
+        arms.push(quote! { Self::#variant => #key, });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    TokenStream::from(quote! {
+        impl #impl_generics i18n_leptos::FluentSelector for #name #ty_generics #where_clause {
+            fn selector(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,55 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use syn::LitStr;
+
+/// The environment variable naming the directory the message IDs referenced by `rtr!` calls
+/// are recorded in. Relative paths are resolved against the `OUT_DIR` of the crate being
+/// compiled if it has a build script, and against its manifest directory otherwise.
+pub const USAGE_DIR_ENV: &str = "I18N_LEPTOS_USAGE_DIR";
+
+/// Returns the usage directory, or `None` if no usage is recorded.
+fn usage_dir() -> Option<PathBuf> {
+    let dir = std::env::var(USAGE_DIR_ENV).ok()?;
+    Some(match std::env::var("OUT_DIR") {
+        Ok(out_dir) => Path::new(&out_dir).join(dir),
+        Err(_) => crate::ftl::resolve_path(&dir),
+    })
+}
+
+/// Records the message `id` and the attributes `attrs` referenced by an `rtr!` call in the
+/// directory set in `I18N_LEPTOS_USAGE_DIR`, if any.
+///
+/// Each reference is written as a line of tab-separated values: the name of the crate,
+/// the location of the call as `file:line:column`, and the message ID, followed by
+/// `.attr` for attributes. Every call has its own file, named after the crate and a hash
+/// of its location, which each expansion of the call overwrites. This keeps no state across
+/// expansions, as a proc macro server (like rust-analyzer's) outlives compilations, so the
+/// files of calls that were moved or removed are only gone once the directory is cleared.
+pub fn record_usage<'a>(
+    id: &LitStr,
+    attrs: impl Iterator<Item = &'a LitStr>,
+) -> Result<(), String> {
+    let Some(dir) = usage_dir() else {
+        return Ok(());
+    };
+
+    let package = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    let span = id.span().unwrap();
+    let location = format!("{}:{}:{}", span.file(), span.line(), span.column());
+    let mut lines = vec![format!("{package}\t{location}\t{}\n", id.value())];
+    for attr in attrs {
+        lines.push(format!(
+            "{package}\t{location}\t{}.{}\n",
+            id.value(),
+            attr.value()
+        ));
+    }
+
+    let mut hasher = DefaultHasher::new();
+    (&package, &location).hash(&mut hasher);
+    let path = dir.join(format!("{package}-{:016x}.tsv", hasher.finish()));
+    // a single write per call, so that a concurrent reader never sees a partial file
+    std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(&path, lines.concat()))
+        .map_err(|err| format!("failed to write usage file '{}': {err}", path.display()))
+}
@@ -0,0 +1,313 @@
+//! Runtime support for the code generated by the `i18n-leptos-macros` crate.
+//!
+//! Nothing in here is part of the public API.
+
+use crate::{MessageError, MessageStatus, ReactiveMessage};
+use leptos::prelude::*;
+
+/// Creates a `ReactiveMessage` that re-runs `query` whenever the language changes,
+/// falling back to `id` if it fails.
+///
+/// The message is derived from the langid by a memo, so it is already resolved on the
+/// first read instead of being empty until an effect has run.
+#[track_caller]
+pub fn reactive_query<F>(id: &'static str, query: F) -> ReactiveMessage
+where
+    F: Fn(&i18n::LanguageIdentifier) -> Result<i18n::Message, Vec<MessageError>>
+        + Send
+        + Sync
+        + 'static,
+{
+    reactive_query_with(id, None, None, query)
+}
+
+/// Like `reactive_query`, but falls back to `default` instead of following the `FallbackPolicy`
+/// if given, and resolves the message in `langid` instead of the context's langid if given.
+#[track_caller]
+pub fn reactive_query_with<F>(
+    id: impl AsRef<str> + Send + Sync + 'static,
+    default: Option<String>,
+    langid: Option<Signal<i18n::LanguageIdentifier>>,
+    query: F,
+) -> ReactiveMessage
+where
+    F: Fn(&i18n::LanguageIdentifier) -> Result<i18n::Message, Vec<MessageError>>
+        + Send
+        + Sync
+        + 'static,
+{
+    // not `unwrap_or_else`, so that a missing context is reported at the `rtr!` call
+    let langid = match langid {
+        Some(langid) => langid,
+        None => crate::expect_langid().into(),
+    };
+    let (msg, status) = split_status(ArcMemo::new_with_compare(
+        move |prev| {
+            crate::locales::track_added_locales();
+            let langid = langid.get();
+            let result = query(&langid);
+            retain_while_loading(prev, id.as_ref(), &langid, result, default.as_deref())
+        },
+        |_, _| true,
+    ));
+
+    ReactiveMessage::new(msg).with_status(status)
+}
+
+/// A message along with the status of its translation.
+pub(crate) type StatusMessage = (i18n::Message, MessageStatus);
+
+/// Returns the resolved message, or the previous message `prev` if the lookup failed
+/// because no message has been resolved for `langid` yet (e.g. as its locales are still
+/// being loaded), so that switching languages doesn't flash message IDs.
+/// Otherwise, failed lookups fall back to `default` if given, or to the `FallbackPolicy`.
+pub(crate) fn retain_while_loading(
+    prev: Option<&StatusMessage>,
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+    result: Result<i18n::Message, Vec<MessageError>>,
+    default: Option<&str>,
+) -> StatusMessage {
+    match (result, prev) {
+        (Ok(msg), _) => (postprocess(msg), MessageStatus::Resolved),
+        (Err(_errs), Some((prev, _))) if !crate::is_locale_loaded(langid) => {
+            (prev.clone(), MessageStatus::Loading)
+        }
+        (Err(errs), _) => {
+            let msg = match default {
+                Some(default) => default_message(id, langid, errs, default),
+                None => fallback_message(id, langid, errs),
+            };
+            (msg, MessageStatus::Fallback)
+        }
+    }
+}
+
+/// Like `retain_while_loading`, but for a message localized by a `LocalizedDisplay`
+/// implementation, which has already fallen back if the lookup failed. A successful lookup
+/// marks its locale as loaded, so the previous message is only kept if it failed.
+pub(crate) fn retain_localized_while_loading(
+    prev: Option<&StatusMessage>,
+    langid: &i18n::LanguageIdentifier,
+    msg: i18n::Message,
+) -> StatusMessage {
+    match prev {
+        Some((prev, _)) if !crate::is_locale_loaded(langid) => {
+            (prev.clone(), MessageStatus::Loading)
+        }
+        _ => (postprocess(msg), MessageStatus::Resolved),
+    }
+}
+
+/// Applies the `BidiIsolation` and pseudo-localization in scope to a resolved message.
+pub(crate) fn postprocess(mut msg: i18n::Message) -> i18n::Message {
+    if crate::direction::use_bidi_isolation() == crate::BidiIsolation::Strip {
+        msg.value = crate::strip_bidi_isolation(&msg.value);
+    }
+    if crate::pseudo::is_pseudo_localized() {
+        msg.value = crate::pseudo_localize(&msg.value);
+    }
+    msg
+}
+
+/// Splits a memo of a message and its status into one memo for each.
+pub(crate) fn split_status(
+    state: ArcMemo<StatusMessage>,
+) -> (ArcMemo<i18n::Message>, ArcMemo<MessageStatus>) {
+    let msg = ArcMemo::new_with_compare(
+        {
+            let state = state.clone();
+            move |_| state.with(|(msg, _)| msg.clone())
+        },
+        |_, _| true,
+    );
+    let status = ArcMemo::new(move |_| state.with(|(_, status)| *status));
+    (msg, status)
+}
+
+/// Creates a `ReactiveMessage` for the message `id` without arguments, which is
+/// resolved through the message cache, see `preload_messages`.
+///
+/// All such messages with the same ID and langid context share a single memo.
+#[track_caller]
+pub fn reactive_cached(locales: &'static i18n::Locales, id: &'static str) -> ReactiveMessage {
+    let langid = crate::expect_langid();
+    let (msg, status) = crate::cache::shared_message(locales, id, langid);
+    ReactiveMessage::new(msg).with_status(status)
+}
+
+/// Creates a `ReactiveMessage` localizing `value` with the arguments returned by `args`,
+/// which is re-run whenever the language or any signal read by `args` changes.
+#[track_caller]
+pub fn reactive_localize_with_args<T, F>(value: T, args: F) -> ReactiveMessage
+where
+    T: crate::LocalizedDisplayWithArgs + Send + Sync + 'static,
+    F: Fn() -> i18n::FluentArgs<'static> + Send + Sync + 'static,
+{
+    let langid = crate::expect_langid();
+    let (msg, status) = split_status(ArcMemo::new_with_compare(
+        move |prev| {
+            crate::locales::track_added_locales();
+            let langid = langid.get();
+            let msg = value.localize_with_args(&langid, &args());
+            retain_localized_while_loading(prev, &langid, msg)
+        },
+        |_, _| true,
+    ));
+
+    ReactiveMessage::new(msg).with_status(status)
+}
+
+/// Queries `locales` for the message `id`, falling back to the locales added with `add_locales`.
+pub fn query(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    query: &i18n::Query,
+) -> Result<i18n::Message, Vec<MessageError>> {
+    crate::locales::query(locales, langid, id, query)
+}
+
+/// Reports the `errors` of a failed lookup of `id` for `langid` and builds the message
+/// used instead, according to the `FallbackPolicy` in scope.
+pub fn fallback_message(
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+    errors: Vec<MessageError>,
+) -> i18n::Message {
+    crate::error::report_error(crate::error::query_error(id, langid, &errors));
+    #[cfg(feature = "dev-warnings")]
+    crate::missing::record_missing_translation(id, langid);
+
+    // signals read by a custom policy mustn't subscribe the lookup to them
+    let policy = use_context::<crate::FallbackPolicy>().unwrap_or_default();
+    i18n::Message {
+        id: id.to_string(),
+        value: untrack(|| policy.fallback_value(id, langid)),
+        attrs: Default::default(),
+    }
+}
+
+/// Like `fallback_message`, but uses `default` as the value regardless of the `FallbackPolicy`.
+pub fn default_message(
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+    errors: Vec<MessageError>,
+    default: &str,
+) -> i18n::Message {
+    let mut msg = fallback_message(id, langid, errors);
+    msg.value = default.to_string();
+    msg
+}
+
+/// Wraps `n` as a numeric Fluent value so that selectors pick the plural category
+/// of the active locale instead of matching a stringified number.
+pub fn plural(n: impl Into<i18n::FluentNumber>) -> i18n::FluentValue<'static> {
+    i18n::FluentValue::Number(n.into())
+}
+
+/// Wraps `n` as a numeric Fluent value that selectors match against the ordinal
+/// category of the active locale (e.g. `one` for 1st/21st, `two` for 2nd in English).
+pub fn ordinal(n: impl Into<i18n::FluentNumber>) -> i18n::FluentValue<'static> {
+    let mut n = n.into();
+    n.options.type_ = i18n::FluentNumberType::Ordinal;
+    i18n::FluentValue::Number(n)
+}
+
+/// Converts `value` into a Fluent value, wrapping strings in bidi isolation marks
+/// if the `BidiIsolation` in scope is `Isolate`.
+pub fn arg(value: impl Into<i18n::FluentValue<'static>>) -> i18n::FluentValue<'static> {
+    match value.into() {
+        i18n::FluentValue::String(value)
+            if crate::direction::use_bidi_isolation() == crate::BidiIsolation::Isolate =>
+        {
+            i18n::FluentValue::String(crate::direction::isolate(&value).into())
+        }
+        value => value,
+    }
+}
+
+/// Converts `value` into the Fluent variant key it selects.
+pub fn select(value: &impl crate::FluentSelector) -> i18n::FluentValue<'static> {
+    i18n::FluentValue::from(value.selector())
+}
+
+/// Adds every argument of `args` to `query`.
+pub fn with_args(query: i18n::Query, args: &impl crate::MessageArgs) -> i18n::Query {
+    args.add_to_query(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{provide_langid_context, set_i18n_error_handler, use_langid_changer, LangIdSource};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn langid(langid: &str) -> i18n::LanguageIdentifier {
+        langid.parse().unwrap()
+    }
+
+    /// Creates a message like `rtr!` does for a message with static arguments, counting
+    /// how often it is resolved into `queries`.
+    fn counted_message(queries: &Arc<AtomicUsize>, resolves: bool) -> ReactiveMessage {
+        let queries = queries.clone();
+        reactive_query("greeting", move |langid| {
+            queries.fetch_add(1, Ordering::Relaxed);
+            match resolves {
+                true => Ok(i18n::Message {
+                    id: "greeting".to_string(),
+                    value: format!("Hello ({langid})"),
+                    attrs: Default::default(),
+                }),
+                false => Err(Vec::new()),
+            }
+        })
+    }
+
+    #[test]
+    fn messages_with_static_arguments_only_re_resolve_on_language_changes() {
+        let owner = Owner::new();
+        owner.with(|| {
+            provide_langid_context(LangIdSource::custom(|| None, |_| {}), Some(langid("en-US")));
+            let changer = use_langid_changer();
+            let queries = Arc::new(AtomicUsize::new(0));
+            let msg = counted_message(&queries, true);
+
+            // an enclosing render re-running for reasons unrelated to the language
+            let unrelated = RwSignal::new(0);
+            let render = Memo::new(move |_| (unrelated.get(), msg.value()));
+            render.get_untracked();
+            assert_eq!(queries.load(Ordering::Relaxed), 1);
+
+            for i in 1..=3 {
+                unrelated.set(i);
+                assert_eq!(render.get_untracked(), (i, "Hello (en-US)".to_string()));
+            }
+            assert_eq!(queries.load(Ordering::Relaxed), 1);
+
+            changer.change(langid("fr-FR"));
+            assert_eq!(render.get_untracked(), (3, "Hello (fr-FR)".to_string()));
+            assert_eq!(queries.load(Ordering::Relaxed), 2);
+        });
+    }
+
+    #[test]
+    fn failed_lookups_dont_subscribe_to_the_error_handler() {
+        let owner = Owner::new();
+        owner.with(|| {
+            provide_langid_context(LangIdSource::custom(|| None, |_| {}), Some(langid("en-US")));
+            let unrelated = RwSignal::new(0);
+            set_i18n_error_handler(move |_| {
+                unrelated.get();
+            });
+            let queries = Arc::new(AtomicUsize::new(0));
+            let msg = counted_message(&queries, false);
+
+            assert_eq!(msg.value(), "greeting");
+            unrelated.set(1);
+            assert_eq!(msg.value(), "greeting");
+            assert_eq!(queries.load(Ordering::Relaxed), 1);
+        });
+    }
+}
@@ -0,0 +1,57 @@
+//! Support for using messages in attribute position in `view!`.
+
+use crate::ReactiveMessage;
+use leptos::prelude::*;
+use leptos::tachys::html::attribute::AttributeValue;
+use leptos::tachys::renderer::types::Element;
+use std::future::Future;
+
+/// The signal a message is rendered through as an attribute value.
+type ValueSignal = Signal<String>;
+
+/// Sets an attribute to the translated value of the message, updating it whenever the
+/// translation changes, e.g. `<button aria-label=rtr!("close")>`.
+///
+/// For an attribute of the message, use `ReactiveMessage::attr_signal`.
+impl AttributeValue for ReactiveMessage {
+    type AsyncOutput = <ValueSignal as AttributeValue>::AsyncOutput;
+    type State = <ValueSignal as AttributeValue>::State;
+    type Cloneable = ValueSignal;
+    type CloneableOwned = ValueSignal;
+
+    fn html_len(&self) -> usize {
+        self.value_untracked().len()
+    }
+
+    fn to_html(self, key: &str, buf: &mut String) {
+        self.into_signal().to_html(key, buf);
+    }
+
+    fn to_template(_key: &str, _buf: &mut String) {}
+
+    fn hydrate<const FROM_SERVER: bool>(self, key: &str, el: &Element) -> Self::State {
+        self.into_signal().hydrate::<FROM_SERVER>(key, el)
+    }
+
+    fn build(self, el: &Element, key: &str) -> Self::State {
+        self.into_signal().build(el, key)
+    }
+
+    fn rebuild(self, key: &str, state: &mut Self::State) {
+        self.into_signal().rebuild(key, state);
+    }
+
+    fn into_cloneable(self) -> Self::Cloneable {
+        self.into_signal()
+    }
+
+    fn into_cloneable_owned(self) -> Self::CloneableOwned {
+        self.into_signal()
+    }
+
+    fn dry_resolve(&mut self) {}
+
+    fn resolve(self) -> impl Future<Output = Self::AsyncOutput> + Send {
+        self.into_signal().resolve()
+    }
+}
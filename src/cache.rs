@@ -0,0 +1,196 @@
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+/// Identifies a resolved message by the address of its `Locales`, its langid and its ID.
+type CacheKey = (usize, i18n::LanguageIdentifier, String);
+
+/// Identifies a shared message by the address of its `Locales`, its ID and the langid
+/// signal of the context it was created in.
+type SharedKey = (usize, &'static str, ArcReadSignal<i18n::LanguageIdentifier>);
+
+/// The memos of a shared message and of its status.
+type SharedMessage = (ArcMemo<i18n::Message>, ArcMemo<crate::MessageStatus>);
+
+/// The lookup of a shared message, along with the langid it was resolved for.
+type SharedLookup = (
+    i18n::LanguageIdentifier,
+    Result<i18n::Message, Vec<crate::MessageError>>,
+);
+
+/// A shared lookup, along with the handle of the messages using it.
+type SharedEntry = (ArcMemo<SharedLookup>, Weak<SharedHandle>);
+
+thread_local! {
+    static MESSAGE_CACHE: RefCell<HashMap<CacheKey, i18n::Message>> = Default::default();
+    static SHARED_MESSAGES: RefCell<HashMap<SharedKey, SharedEntry>> = Default::default();
+}
+
+/// Held by every message using a shared lookup, which is removed from the cache
+/// once the last of them is dropped.
+struct SharedHandle {
+    key: SharedKey,
+}
+
+impl Drop for SharedHandle {
+    fn drop(&mut self) {
+        // the cache may already be gone if the messages are dropped on thread exit
+        _ = SHARED_MESSAGES.try_with(|shared| {
+            let Ok(mut shared) = shared.try_borrow_mut() else {
+                return;
+            };
+            // the entry may have been replaced by a newer lookup since, which is kept
+            if shared
+                .get(&self.key)
+                .is_some_and(|(_, handle)| handle.strong_count() == 0)
+            {
+                shared.remove(&self.key);
+            }
+        });
+    }
+}
+
+fn cache_key(
+    locales: &'static i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+) -> CacheKey {
+    (
+        locales as *const i18n::Locales as usize,
+        langid.clone(),
+        id.to_string(),
+    )
+}
+
+/// Resolves the message `id` without arguments for `langid`, querying `locales` only if
+/// it isn't cached yet. Failed lookups aren't cached.
+pub(crate) fn cached_query(
+    locales: &'static i18n::Locales,
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+) -> Result<i18n::Message, Vec<crate::MessageError>> {
+    let key = cache_key(locales, langid, id);
+    if let Some(msg) = MESSAGE_CACHE.with_borrow(|cache| cache.get(&key).cloned()) {
+        return Ok(msg);
+    }
+
+    let msg = crate::locales::query(locales, langid, id, &i18n::Query::new(id))?;
+    MESSAGE_CACHE.with_borrow_mut(|cache| cache.insert(key, msg.clone()));
+    Ok(msg)
+}
+
+/// Like `cached_query`, but failed lookups fall back to the message ID.
+pub(crate) fn cached_message(
+    locales: &'static i18n::Locales,
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+) -> i18n::Message {
+    cached_query(locales, id, langid)
+        .unwrap_or_else(|errs| crate::__private::fallback_message(id, langid, errs))
+}
+
+/// Returns the memos of the message `id` without arguments for the context's `langid` and
+/// of its status.
+///
+/// The lookup is shared by every message of the same ID and context instead of being
+/// re-resolved for each of them, and removed from the cache once the last of them is dropped.
+/// It reads no context, as it runs under the owner of the first message: the `BidiIsolation`,
+/// pseudo-localization and `FallbackPolicy` in scope are applied by the returned memos,
+/// so they are those of the caller.
+pub(crate) fn shared_message(
+    locales: &'static i18n::Locales,
+    id: &'static str,
+    langid: ArcReadSignal<i18n::LanguageIdentifier>,
+) -> SharedMessage {
+    let key = (locales as *const i18n::Locales as usize, id, langid.clone());
+    let (lookup, handle) = SHARED_MESSAGES.with_borrow_mut(|shared| {
+        let existing = shared
+            .get(&key)
+            .and_then(|(lookup, handle)| Some((lookup.clone(), handle.upgrade()?)));
+        if let Some(existing) = existing {
+            return existing;
+        }
+
+        let lookup = ArcMemo::new_with_compare(
+            move |_| {
+                crate::locales::track_added_locales();
+                let langid = langid.get();
+                let result = cached_query(locales, id, &langid);
+                (langid, result)
+            },
+            |_, _| true,
+        );
+        let handle = Arc::new(SharedHandle { key: key.clone() });
+        shared.insert(key, (lookup.clone(), Arc::downgrade(&handle)));
+        (lookup, handle)
+    });
+
+    crate::__private::split_status(ArcMemo::new_with_compare(
+        move |prev| {
+            let _handle = &handle;
+            lookup.with(|(langid, result)| {
+                crate::__private::retain_while_loading(prev, id, langid, result.clone(), None)
+            })
+        },
+        |_, _| true,
+    ))
+}
+
+/// Resolves the messages in `ids` for `langid` ahead of time, e.g. on a route transition,
+/// so that `rtr!` calls for them don't have to query Fluent while rendering.
+///
+/// Only messages without arguments are cached, which is what `rtr!` calls without
+/// arguments and `rtr_all` look up. Entries are keyed by langid, so a language change
+/// doesn't invalidate them: preloading the next language before switching to it makes
+/// the switch itself cheap, and switching back reuses the entries of the old language.
+/// Use `clear_message_cache` to free them.
+pub fn preload_messages(
+    locales: &'static i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    ids: &[&str],
+) {
+    for id in ids {
+        cached_message(locales, id, langid);
+    }
+}
+
+/// Removes the resolved messages from the cache, keeping the shared messages.
+pub(crate) fn clear_resolved_messages() {
+    MESSAGE_CACHE.with_borrow_mut(|cache| cache.clear());
+}
+
+/// Removes every resolved message from the cache, e.g. after the `Locales`
+/// have been reloaded or to free the messages of languages no longer in use.
+///
+/// This also drops the shared lookups of `rtr!` calls without arguments, so that the messages
+/// created afterwards look them up again. Messages that are already rendered keep their
+/// value until they are re-resolved.
+pub fn clear_message_cache() {
+    clear_resolved_messages();
+    SHARED_MESSAGES.with_borrow_mut(|shared| shared.clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adding_locales_drops_the_resolved_messages() {
+        let locales: &'static i18n::Locales = Box::leak(Box::default());
+        let langid: i18n::LanguageIdentifier = "en-US".parse().unwrap();
+        let msg = i18n::Message {
+            id: "greeting".to_string(),
+            value: "Hello".to_string(),
+            attrs: Default::default(),
+        };
+        MESSAGE_CACHE
+            .with_borrow_mut(|cache| cache.insert(cache_key(locales, &langid, "greeting"), msg));
+        let cached = cached_query(locales, "greeting", &langid).map(|msg| msg.value);
+        assert_eq!(cached.ok().as_deref(), Some("Hello"));
+
+        // the added locales are empty, so the message is no longer resolved once re-queried
+        crate::add_locales(Box::leak(Box::default()));
+        assert!(cached_query(locales, "greeting", &langid).is_err());
+    }
+}
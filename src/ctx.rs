@@ -1,47 +1,437 @@
 use crate::utils;
 use leptos::prelude::*;
 use std::str::FromStr;
-use web_sys::wasm_bindgen::UnwrapThrowExt;
+use std::sync::Arc;
+use thiserror::Error;
 
 /// Defines the source from which the `LanguageIdentifier` is obtained.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub enum LangIdSource {
     /// The language identifier is obtained from the browser's navigator language.
+    /// Changes are applied, but not persisted across reloads.
     Navigator,
     /// The language identifier is stored in and retrieved from local storage.
     LocalStorage(String),
+    /// The language identifier is stored and retrieved by the given functions, e.g. as part
+    /// of an app settings blob. `load` returns the stored langid, if any, and `store` is
+    /// called with the new langid whenever it is changed with `change_langid`, or with an
+    /// empty string when it is reset with `reset_langid_to_default`, which should clear it.
+    Custom {
+        load: Arc<dyn Fn() -> Option<String>>,
+        store: Arc<dyn Fn(&str)>,
+    },
 }
 
-/// Newtype wrapper around a langid signal used to pass it around via contexts.
+impl LangIdSource {
+    /// A `LangIdSource::Custom` with the given functions.
+    pub fn custom(
+        load: impl Fn() -> Option<String> + 'static,
+        store: impl Fn(&str) + 'static,
+    ) -> Self {
+        Self::Custom {
+            load: Arc::new(load),
+            store: Arc::new(store),
+        }
+    }
+}
+
+impl std::fmt::Debug for LangIdSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Navigator => write!(f, "Navigator"),
+            Self::LocalStorage(key) => f.debug_tuple("LocalStorage").field(key).finish(),
+            Self::Custom { .. } => write!(f, "Custom {{ .. }}"),
+        }
+    }
+}
+
+/// Custom sources are equal if they share the same functions.
+impl PartialEq for LangIdSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Navigator, Self::Navigator) => true,
+            (Self::LocalStorage(a), Self::LocalStorage(b)) => a == b,
+            (
+                Self::Custom { load, store },
+                Self::Custom {
+                    load: other_load,
+                    store: other_store,
+                },
+            ) => Arc::ptr_eq(load, other_load) && Arc::ptr_eq(store, other_store),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for LangIdSource {}
+
+/// Wrapper around a langid signal used to pass it around via contexts.
 #[derive(Debug, Clone)]
-struct LangIdContext(ArcRwSignal<i18n::LanguageIdentifier>);
+struct LangIdContext {
+    langid: ArcRwSignal<i18n::LanguageIdentifier>,
+    /// The name of the custom event used to notify the context of language changes.
+    event_name: Arc<str>,
+}
+
+/// Newtype wrapper around the locales the application has translations for.
+#[derive(Debug, Clone)]
+struct AvailableLocalesContext(Arc<[i18n::LanguageIdentifier]>);
+
+/// Provides the locales the application has translations for to the Leptos context.
+///
+/// When provided before `provide_langid_context`, langids read from persistent storage
+/// or passed to `change_langid` are negotiated against this list, falling back to the
+/// initial langid if none of them match.
+pub fn provide_available_locales(locales: impl Into<Vec<i18n::LanguageIdentifier>>) {
+    provide_context(AvailableLocalesContext(locales.into().into()));
+}
+
+/// A utility function for getting the available locales from the Leptos context.
+/// Returns `None` if no available locales are provided.
+pub fn use_available_locales() -> Option<Arc<[i18n::LanguageIdentifier]>> {
+    use_context::<AvailableLocalesContext>().map(|ctx| ctx.0)
+}
+
+/// Returns the available locale best matching the `requested` ones, which are in order of
+/// preference, e.g. the languages of the navigator or an `Accept-Language` header.
+///
+/// Each requested langid is matched exactly first, then by language and script, and then
+/// by language only, so `fr-CA` picks `fr-FR` if there is no `fr-CA`. The first requested
+/// langid with a match wins. Returns `None` if none of them match.
+pub fn negotiate(
+    requested: &[i18n::LanguageIdentifier],
+    available: &[i18n::LanguageIdentifier],
+) -> Option<i18n::LanguageIdentifier> {
+    requested
+        .iter()
+        .find_map(|requested| {
+            available
+                .iter()
+                .find(|available| *available == requested)
+                .or_else(|| {
+                    available.iter().find(|available| {
+                        available.language == requested.language
+                            && available.script == requested.script
+                    })
+                })
+                .or_else(|| {
+                    available
+                        .iter()
+                        .find(|available| available.language == requested.language)
+                })
+        })
+        .cloned()
+}
+
+/// Negotiates `langid` against the available locales, see `negotiate`.
+/// Returns `langid` unchanged if there are no available locales to match against,
+/// or the matching available locale otherwise, which is what the messages are looked up in.
+fn clamp_langid(
+    langid: i18n::LanguageIdentifier,
+    available: Option<&[i18n::LanguageIdentifier]>,
+) -> Option<i18n::LanguageIdentifier> {
+    match available {
+        Some(available) => negotiate(std::slice::from_ref(&langid), available),
+        None => Some(langid),
+    }
+}
 
 /// A utility function for getting the langid signal from the Leptos context.
 /// Returns `None` if no `LangIdContext` is provided.
 pub fn use_langid() -> Option<ArcReadSignal<i18n::LanguageIdentifier>> {
-    use_context::<LangIdContext>().map(|ctx| ctx.0.read_only())
+    use_context::<LangIdContext>().map(|ctx| ctx.langid.read_only())
+}
+
+/// Returns the writable langid signal of the `LangIdContext`, for integrations that
+/// drive the langid directly instead of through `change_langid`.
+pub(crate) fn use_langid_rw_signal() -> Option<ArcRwSignal<i18n::LanguageIdentifier>> {
+    use_context::<LangIdContext>().map(|ctx| ctx.langid)
 }
 
 /// A utility function for getting the langid signal from the Leptos context.
-/// Panics if no `LangIdContext` is provided.
+/// Panics if no `LangIdContext` is provided, or if called outside of a reactive owner,
+/// where no context can be provided at all.
+#[track_caller]
 pub fn expect_langid() -> ArcReadSignal<i18n::LanguageIdentifier> {
-    use_langid().unwrap()
+    match use_langid() {
+        Some(langid) => langid,
+        None if leptos::reactive::owner::Owner::current().is_none() => panic!(
+            "`rtr!` and other reactive translations must be created within a reactive owner, \
+             e.g. in a component body; outside of one, like in event handlers or at \
+             initialization, translate with `translate_in` or read an existing message instead"
+        ),
+        None => panic!(
+            "no `LangIdContext` is provided; call `provide_langid_context` in a parent component"
+        ),
+    }
+}
+
+/// Like `use_langid`, but provides a `LangIdContext` with the navigator language if there
+/// is none, e.g. for components of a library that should work in apps which don't set up
+/// localization, instead of panicking like `expect_langid`.
+///
+/// The context is provided to the current component and its children, like with
+/// `provide_langid_context(LangIdSource::Navigator, None)`, so sibling components each get
+/// their own, which all follow language changes. There is no navigator on the server, so
+/// server-rendered apps start with the first available locale or `en-US` there, while the
+/// client starts with the navigator language, which breaks hydration if they differ. Such
+/// apps should provide the context themselves, e.g. with `provide_hydrated_langid_context`.
+pub fn use_langid_or_provide_default() -> ArcReadSignal<i18n::LanguageIdentifier> {
+    if let Some(langid) = use_langid() {
+        return langid;
+    }
+    provide_langid_context(LangIdSource::Navigator, None);
+    expect_langid()
+}
+
+/// Like `expect_langid`, but returns the langid as a `Copy` `Signal`, which can be moved
+/// into any number of closures without cloning it first.
+/// Panics if no `LangIdContext` is provided.
+#[track_caller]
+pub fn use_langid_signal() -> Signal<i18n::LanguageIdentifier> {
+    expect_langid().into()
+}
+
+/// Runs `callback` with the new langid whenever the language changes, e.g. to refetch
+/// locale-dependent data. Panics if no `LangIdContext` is provided.
+///
+/// The callback doesn't run for the initial langid, only for subsequent changes.
+/// It stops running once the current owner is cleaned up, or when the returned
+/// effect is stopped with `Effect::stop`.
+pub fn on_langid_change(
+    callback: impl Fn(&i18n::LanguageIdentifier) + 'static,
+) -> Effect<LocalStorage> {
+    let langid = expect_langid();
+    Effect::watch(
+        move || langid.get(),
+        move |langid, _, _| callback(langid),
+        false,
+    )
+}
+
+/// Returns a signal of the previous and the current langid, e.g. for cross-fading between
+/// the old and the new text when switching languages.
+/// Panics if no `LangIdContext` is provided.
+///
+/// The previous langid is `None` until the language changes for the first time.
+pub fn use_langid_transition(
+) -> Signal<(Option<i18n::LanguageIdentifier>, i18n::LanguageIdentifier)> {
+    let langid = expect_langid();
+    type Transition = (Option<i18n::LanguageIdentifier>, i18n::LanguageIdentifier);
+    let transition = Memo::new(move |prev: Option<&Transition>| {
+        let current = langid.get();
+        match prev {
+            // the langid was set to the same value, so nothing changed
+            Some(prev) if prev.1 == current => prev.clone(),
+            Some((_, prev)) => (Some(prev.clone()), current),
+            None => (None, current),
+        }
+    });
+    transition.into()
+}
+
+/// A utility function for getting the langid from the Leptos context as a string signal,
+/// e.g. for `data-*` attributes or API headers.
+/// Returns `None` if no `LangIdContext` is provided.
+pub fn use_langid_string() -> Option<Signal<String>> {
+    use_langid().map(|langid| Signal::derive(move || langid.get().to_string()))
+}
+
+/// The default custom event name.
+pub const DEFAULT_LANGID_CHANGE_EVENT: &str = "i18n-lang-change-notification";
+
+/// Returns the custom event name of the `LangIdContext`, or the default one if no context is
+/// provided, which is reported, as contexts with a custom event name then miss the change.
+fn langid_change_event_name() -> Arc<str> {
+    match use_context::<LangIdContext>() {
+        Some(ctx) => ctx.event_name,
+        None => {
+            crate::error::report_error(crate::I18nError::LangId(format!(
+                "no LangIdContext in scope to change the langid of, notifying the default \
+                 event name '{DEFAULT_LANGID_CHANGE_EVENT}'; use `use_langid_changer` to change \
+                 the langid outside of the component tree, e.g. in a spawned task"
+            )));
+            DEFAULT_LANGID_CHANGE_EVENT.into()
+        }
+    }
+}
+
+/// Changes the langid of the `LangIdContext` it was created in, see `use_langid_changer`.
+#[derive(Debug, Clone)]
+pub struct LangIdChanger {
+    event_name: Arc<str>,
+}
+
+impl LangIdChanger {
+    /// Changes the langid, like `change_langid`.
+    pub fn change(&self, langid: i18n::LanguageIdentifier) {
+        dispatch_langid_change(langid, &self.event_name);
+    }
+
+    /// Reverts the langid to the default one, like `reset_langid_to_default`.
+    pub fn reset(&self) {
+        utils::langid_events::dispatch(&self.event_name, "");
+    }
 }
 
-/// The custom event name.
-const LANGID_EVENT_CHANGE_NAME: &'static str = "i18n-lang-change-notification";
+/// Returns a handle changing the langid of the `LangIdContext` in scope, which keeps working
+/// outside of the component tree, e.g. in a spawned task or in a callback that runs after
+/// the component was unmounted, where `change_langid` can't find the context anymore.
+///
+/// The event name of the context is captured when this is called, so it must be called
+/// within the component tree the context was provided to. Reports an error and falls back
+/// to the default event name otherwise.
+pub fn use_langid_changer() -> LangIdChanger {
+    LangIdChanger {
+        event_name: langid_change_event_name(),
+    }
+}
 
 /// Changes the current language identifier and dispatches a custom event to notify listeners.
+///
+/// The event name is taken from the `LangIdContext` in scope, so this must be called within
+/// the component tree the context was provided to, which is reported otherwise. Outside of
+/// it, use the handle returned by `use_langid_changer`.
+///
+/// The langid is passed to the context as is, variants included, unless available locales
+/// are provided: it is then replaced by the available locale it negotiates to, see `negotiate`,
+/// so `de-DE-1996` becomes `de-DE` if that is the available one. Unicode extensions like
+/// `-u-ca-gregory` aren't part of a `LanguageIdentifier`, so stored langids with extensions
+/// are reported as invalid rather than stripped.
 pub fn change_langid(langid: i18n::LanguageIdentifier) {
-    let langid = langid.to_string();
-    let custom_event_init = web_sys::CustomEventInit::new();
-    custom_event_init.set_detail(&langid.into());
-    let custom_event = leptos::ev::CustomEvent::new_with_event_init_dict(
-        LANGID_EVENT_CHANGE_NAME,
-        &custom_event_init,
-    )
-    .expect("should pass always");
-    _ = window().dispatch_event(&custom_event);
+    dispatch_langid_change(langid, &langid_change_event_name());
+}
+
+/// Like `change_langid`, but resolves once the UI has been updated to the new language,
+/// e.g. to take a screenshot or run an assertion right after switching languages.
+///
+/// The langid signal is set synchronously, which marks every `rtr!` memo depending on it
+/// as stale. Memos are re-resolved lazily when read, while the effects reading them (such
+/// as the ones updating the DOM) are scheduled on Leptos's executor. This future waits
+/// for the next tick of the executor, by which the scheduled effects have run, so the DOM
+/// shows the new language. It doesn't wait for the browser to paint, nor for locales that
+/// are still being loaded, see `ReactiveMessage::loading`.
+pub async fn change_langid_and_settle(langid: i18n::LanguageIdentifier) {
+    change_langid(langid);
+    leptos::task::Executor::tick().await;
+}
+
+thread_local! {
+    /// The langid being dispatched by `change_langid`, which the listeners take as is instead
+    /// of parsing it back from the event, so that nothing is lost in the round trip.
+    static DISPATCHED_LANGID: std::cell::RefCell<Option<i18n::LanguageIdentifier>> =
+        Default::default();
+}
+
+fn dispatch_langid_change(langid: i18n::LanguageIdentifier, event_name: &str) {
+    let langid_string = langid.to_string();
+    DISPATCHED_LANGID.with_borrow_mut(|dispatched| *dispatched = Some(langid));
+    utils::langid_events::dispatch(event_name, &langid_string);
+    DISPATCHED_LANGID.with_borrow_mut(|dispatched| *dispatched = None);
+}
+
+/// Returns the langid of a change notification or of persistent storage: the structured
+/// langid if it was dispatched by `change_langid` on this thread, or the parsed string
+/// otherwise. Invalid strings are reported and return `None`.
+fn notified_langid(langid: &str) -> Option<i18n::LanguageIdentifier> {
+    let dispatched = DISPATCHED_LANGID.with_borrow(|dispatched| {
+        dispatched
+            .clone()
+            .filter(|dispatched| dispatched.to_string() == langid)
+    });
+    dispatched.or_else(|| match i18n::LanguageIdentifier::from_str(langid) {
+        Ok(langid) => Some(langid),
+        Err(err) => {
+            crate::error::report_error(crate::I18nError::LangId(format!(
+                "'{langid}' is not a valid language identifier: {err:?}"
+            )));
+            None
+        }
+    })
+}
+
+/// Reverts the language to the system default, e.g. for a "Use system language" setting.
+///
+/// The persisted langid is cleared, and the navigator language is used again, negotiated
+/// against the available locales and falling back like in `provide_langid_context`.
+/// Like `change_langid`, this notifies the contexts listening on the event name in scope.
+pub fn reset_langid_to_default() {
+    // an empty langid is the reset notification
+    utils::langid_events::dispatch(&langid_change_event_name(), "");
+}
+
+/// An error returned by `try_change_langid`, or reported for an invalid initial langid
+/// passed to `provide_langid_context_str`.
+#[derive(Error, Debug)]
+pub enum LangIdParseError {
+    #[error("'{0}' is not a valid language identifier")]
+    Invalid(String),
+    #[error("'{0}' doesn't match any of the available locales")]
+    Unavailable(String),
+}
+
+/// Parses `langid` and changes the current language identifier to it, see `change_langid`.
+///
+/// If available locales are provided, the parsed langid is negotiated against them
+/// and the best match is used instead.
+pub fn try_change_langid(langid: &str) -> Result<(), LangIdParseError> {
+    change_langid(parse_available_langid(langid)?);
+    Ok(())
+}
+
+/// Parses `langid` and negotiates it against the available locales, if provided.
+fn parse_available_langid(langid: &str) -> Result<i18n::LanguageIdentifier, LangIdParseError> {
+    let parsed = i18n::LanguageIdentifier::from_str(langid)
+        .map_err(|_| LangIdParseError::Invalid(langid.to_string()))?;
+    let available = use_available_locales();
+    clamp_langid(parsed, available.as_deref())
+        .ok_or_else(|| LangIdParseError::Unavailable(langid.to_string()))
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// The pending timeout of the last `change_langid_debounced` call for each event name,
+    /// so that contexts listening on different event names are debounced independently.
+    static PENDING_LANGID_CHANGES: std::cell::RefCell<std::collections::HashMap<Arc<str>, TimeoutHandle>> =
+        Default::default();
+}
+
+/// Like `change_langid`, but coalesces rapid calls: the change is only dispatched once
+/// no other debounced change has been requested for `delay`, and only the last langid wins.
+/// Calls notifying different event names don't cancel each other.
+///
+/// Every `rtr!` message re-resolves on a language change, so this is useful when changes
+/// can fire many times per second (e.g. a live-preview locale editor). The tradeoff is
+/// that the UI only updates `delay` after the last call. `change_langid` stays immediate.
+///
+/// Only available on `wasm32`, as it relies on the browser's timers.
+#[cfg(target_arch = "wasm32")]
+pub fn change_langid_debounced(langid: i18n::LanguageIdentifier, delay: std::time::Duration) {
+    // resolve the event name now, as the timeout runs outside of the reactive owner
+    let event_name = langid_change_event_name();
+    if let Some(handle) =
+        PENDING_LANGID_CHANGES.with_borrow_mut(|pending| pending.remove(&event_name))
+    {
+        handle.clear();
+    }
+    let timeout = set_timeout_with_handle(
+        {
+            let event_name = event_name.clone();
+            move || {
+                PENDING_LANGID_CHANGES.with_borrow_mut(|pending| pending.remove(&event_name));
+                dispatch_langid_change(langid, &event_name);
+            }
+        },
+        delay,
+    );
+    match timeout {
+        Ok(handle) => {
+            PENDING_LANGID_CHANGES.with_borrow_mut(|pending| pending.insert(event_name, handle));
+        }
+        Err(err) => crate::error::report_error(crate::I18nError::LangId(format!(
+            "failed to schedule debounced langid change: {err:?}"
+        ))),
+    }
 }
 
 /// Provides the `LangIdContext` to the Leptos context, initializing the language identifier
@@ -49,65 +439,434 @@ pub fn change_langid(langid: i18n::LanguageIdentifier) {
 ///
 /// This function sets up the reactive language identifier and handles its persistence
 /// and updates based on the chosen source (Navigator, LocalStorage, or Cookie).
+///
+/// The context can be provided again deeper in the tree, in which case it shadows the
+/// parent context for that subtree, see `provide_scoped_langid_context`.
+///
+/// Without an `initial_langid`, the navigator language is used, negotiated against the
+/// available locales if provided. If that fails, the first available locale is used,
+/// or `en-US` if none are provided. Use `provide_langid_context_with_default` to pick
+/// the fallback explicitly, or `LangIdContextBuilder` to combine several options.
 pub fn provide_langid_context(
     source: LangIdSource,
     initial_langid: Option<i18n::LanguageIdentifier>,
 ) {
-    let initial_langid = match initial_langid {
-        Some(langid) => langid,
-        None => {
-            let langid = window()
-                .navigator()
-                .language()
-                .unwrap_or_else(move || "en-US".to_string());
-            let langid = i18n::LanguageIdentifier::from_str(&langid).unwrap_throw();
-            langid
+    LangIdContextBuilder::new(source)
+        .initial(initial_langid)
+        .provide();
+}
+
+/// Like `provide_langid_context`, but takes the initial langid as a string, e.g. from a config
+/// file, a cookie or a server value, which is parsed and negotiated against the available
+/// locales if provided, like `try_change_langid`.
+///
+/// An initial langid that is invalid or doesn't match any available locale is reported to the
+/// error handler and ignored, so the navigator language or the default is used instead.
+pub fn provide_langid_context_str(source: LangIdSource, initial_langid: Option<&str>) {
+    let initial_langid = initial_langid.and_then(|langid| match parse_available_langid(langid) {
+        Ok(langid) => Some(langid),
+        Err(err) => {
+            crate::error::report_error(crate::I18nError::LangId(format!(
+                "ignoring the initial langid: {err}"
+            )));
+            None
+        }
+    });
+    provide_langid_context(source, initial_langid);
+}
+
+/// Like `provide_langid_context`, but falls back to `default_langid` when no `initial_langid`
+/// is given and the navigator language is unavailable or not among the available locales.
+pub fn provide_langid_context_with_default(
+    source: LangIdSource,
+    initial_langid: Option<i18n::LanguageIdentifier>,
+    default_langid: i18n::LanguageIdentifier,
+) {
+    LangIdContextBuilder::new(source)
+        .initial(initial_langid)
+        .default_langid(default_langid)
+        .provide();
+}
+
+/// Like `provide_langid_context`, but listens for language changes on a custom event name
+/// instead of `DEFAULT_LANGID_CHANGE_EVENT`.
+///
+/// This prevents cross-talk between independent instances sharing a window (e.g. micro-frontends).
+/// `change_langid` picks the event name up from the context, so both sides agree.
+pub fn provide_langid_context_with_event_name(
+    source: LangIdSource,
+    initial_langid: Option<i18n::LanguageIdentifier>,
+    event_name: impl Into<Arc<str>>,
+) {
+    LangIdContextBuilder::new(source)
+        .initial(initial_langid)
+        .event_name(event_name)
+        .provide();
+}
+
+/// Like `provide_langid_context`, but for hydrating a server-rendered app: the context
+/// starts with `server_langid`, the language the server rendered with, so that the
+/// hydrated text and `<html lang>` match the server output exactly.
+///
+/// The navigator language is ignored, and a persisted langid is only read after the first
+/// animation frame, once hydration is done. If it differs from `server_langid`, the language
+/// is switched then, which is unavoidable if the server can't know the persisted value.
+/// Persisting the langid in a cookie avoids that, see `langid_from_cookie`.
+pub fn provide_hydrated_langid_context(
+    source: LangIdSource,
+    server_langid: i18n::LanguageIdentifier,
+) {
+    LangIdContextBuilder::new(source)
+        .initial(server_langid)
+        .hydrate(true)
+        .provide();
+}
+
+/// Returns the langid stored in the cookie `name` of a `Cookie` request header,
+/// if it is set to a valid language identifier.
+///
+/// Servers can pass it as the `server_langid` of `provide_hydrated_langid_context`, while
+/// a `LangIdSource::custom` keeps the cookie up to date on the client, so that the server
+/// renders in the persisted language and hydration doesn't switch languages.
+pub fn langid_from_cookie(cookie_header: &str, name: &str) -> Option<i18n::LanguageIdentifier> {
+    cookie_header
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| i18n::LanguageIdentifier::from_str(value.trim_matches('"')).ok())
+}
+
+/// Returns the navigator language negotiated against the available locales, if any.
+#[cfg(target_arch = "wasm32")]
+fn navigator_langid(
+    available: Option<&[i18n::LanguageIdentifier]>,
+) -> Option<i18n::LanguageIdentifier> {
+    let langid = window().navigator().language()?;
+    let langid = i18n::LanguageIdentifier::from_str(&langid).ok()?;
+    clamp_langid(langid, available)
+}
+
+/// There is no navigator outside of the browser.
+#[cfg(not(target_arch = "wasm32"))]
+fn navigator_langid(
+    _available: Option<&[i18n::LanguageIdentifier]>,
+) -> Option<i18n::LanguageIdentifier> {
+    None
+}
+
+/// The configuration of a `LangIdContext`.
+#[derive(Debug)]
+struct LangIdConfig {
+    source: LangIdSource,
+    initial_langid: Option<i18n::LanguageIdentifier>,
+    /// The langid used if there is no initial langid and the navigator language is unusable.
+    default_langid: Option<i18n::LanguageIdentifier>,
+    event_name: Arc<str>,
+    /// Whether reading the persisted langid is deferred until hydration is done.
+    hydrate: bool,
+}
+
+impl LangIdConfig {
+    fn new(source: LangIdSource) -> Self {
+        Self {
+            source,
+            initial_langid: None,
+            default_langid: None,
+            event_name: DEFAULT_LANGID_CHANGE_EVENT.into(),
+            hydrate: false,
+        }
+    }
+}
+
+/// A builder for providing a `LangIdContext` with any combination of the options of the
+/// `provide_langid_context*` functions, along with the contexts configuring it.
+///
+/// ```ignore
+/// LangIdContextBuilder::new(LangIdSource::LocalStorage("lang".into()))
+///     .available(["en-US", "fr-FR"].map(|langid| langid.parse().unwrap()))
+///     .default_langid("en-US".parse().unwrap())
+///     .fallback_policy(FallbackPolicy::Empty)
+///     .provide();
+/// ```
+#[derive(Debug)]
+#[must_use = "the context is only provided by `provide`"]
+pub struct LangIdContextBuilder {
+    config: LangIdConfig,
+    available: Option<Vec<i18n::LanguageIdentifier>>,
+    fallback_policy: Option<crate::FallbackPolicy>,
+}
+
+impl LangIdContextBuilder {
+    /// A builder for a context whose langid is persisted according to `source`.
+    pub fn new(source: LangIdSource) -> Self {
+        Self {
+            config: LangIdConfig::new(source),
+            available: None,
+            fallback_policy: None,
+        }
+    }
+
+    /// Sets the initial langid, which takes precedence over the navigator language,
+    /// see `provide_langid_context`.
+    pub fn initial(mut self, langid: impl Into<Option<i18n::LanguageIdentifier>>) -> Self {
+        self.config.initial_langid = langid.into();
+        self
+    }
+
+    /// Sets the langid used if there is no initial langid and the navigator language is
+    /// unavailable or not among the available locales, see `provide_langid_context_with_default`.
+    pub fn default_langid(mut self, langid: i18n::LanguageIdentifier) -> Self {
+        self.config.default_langid = Some(langid);
+        self
+    }
+
+    /// Provides the locales the application has translations for along with the context,
+    /// see `provide_available_locales`.
+    pub fn available(mut self, locales: impl Into<Vec<i18n::LanguageIdentifier>>) -> Self {
+        self.available = Some(locales.into());
+        self
+    }
+
+    /// Sets the name of the event language changes are notified with,
+    /// see `provide_langid_context_with_event_name`.
+    pub fn event_name(mut self, event_name: impl Into<Arc<str>>) -> Self {
+        self.config.event_name = event_name.into();
+        self
+    }
+
+    /// Sets whether the app is hydrated from server-rendered HTML, in which case the initial
+    /// langid is the one the server rendered with, see `provide_hydrated_langid_context`.
+    pub fn hydrate(mut self, hydrate: bool) -> Self {
+        self.config.hydrate = hydrate;
+        self
+    }
+
+    /// Provides the `FallbackPolicy` along with the context, see `provide_fallback_policy`.
+    pub fn fallback_policy(mut self, policy: crate::FallbackPolicy) -> Self {
+        self.fallback_policy = Some(policy);
+        self
+    }
+
+    /// Provides the `LangIdContext` and the configured contexts to the current component
+    /// and its children.
+    pub fn provide(self) {
+        if let Some(available) = self.available {
+            provide_available_locales(available);
+        }
+        if let Some(policy) = self.fallback_policy {
+            crate::provide_fallback_policy(policy);
         }
+        provide_langid_context_impl(self.config);
+    }
+}
+
+fn provide_langid_context_impl(config: LangIdConfig) {
+    let LangIdConfig {
+        source,
+        initial_langid,
+        default_langid,
+        event_name,
+        hydrate,
+    } = config;
+    // the langid used if none is given or persisted, which `reset_langid_to_default` reverts to
+    let available = use_available_locales();
+    let system_langid: Arc<dyn Fn() -> i18n::LanguageIdentifier> = Arc::new(move || {
+        navigator_langid(available.as_deref())
+            .or_else(|| default_langid.clone())
+            .or_else(|| {
+                available
+                    .as_ref()
+                    .and_then(|available| available.first().cloned())
+            })
+            .unwrap_or_else(|| i18n::LanguageIdentifier::from_str("en-US").expect("valid langid"))
+    });
+    let initial_langid = match initial_langid {
+        Some(initial_langid) => initial_langid,
+        None => system_langid(),
     };
     let langid = ArcRwSignal::new(initial_langid.clone());
 
-    provide_context(LangIdContext(langid.clone()));
+    provide_context(LangIdContext {
+        langid: langid.clone(),
+        event_name: event_name.clone(),
+    });
 
     match source {
-        LangIdSource::Navigator => {}
+        LangIdSource::Navigator => {
+            // nothing is persisted, but changes and resets still have to be handled
+            setup_persistence_handler(
+                langid,
+                initial_langid,
+                system_langid,
+                Arc::new(|| None),
+                Arc::new(|_: &str| {}),
+                event_name,
+                hydrate,
+            );
+        }
         LangIdSource::LocalStorage(key) => {
-            setup_local_storage_handler(langid, initial_langid, key);
+            let load = {
+                let key = key.clone();
+                move || match utils::local_storage::get(&key) {
+                    Ok(langid) => langid,
+                    Err(err) => {
+                        crate::error::report_error(crate::I18nError::LangId(format!(
+                            "failed to read langid from local storage: {err}"
+                        )));
+                        None
+                    }
+                }
+            };
+            let store = move |langid: &str| {
+                let result = if langid.is_empty() {
+                    utils::local_storage::remove(&key)
+                } else {
+                    utils::local_storage::set(&key, langid)
+                };
+                if let Err(err) = result {
+                    crate::error::report_error(crate::I18nError::LangId(format!(
+                        "failed to store langid in local storage: {err}"
+                    )));
+                }
+            };
+            setup_persistence_handler(
+                langid,
+                initial_langid,
+                system_langid,
+                Arc::new(load),
+                Arc::new(store),
+                event_name,
+                hydrate,
+            );
+        }
+        LangIdSource::Custom { load, store } => {
+            setup_persistence_handler(
+                langid,
+                initial_langid,
+                system_langid,
+                load,
+                store,
+                event_name,
+                hydrate,
+            );
         }
     }
 }
 
-fn setup_local_storage_handler(
+/// The event name of scoped contexts, which nothing listens for.
+const SCOPED_LANGID_CHANGE_EVENT: &str = "i18n-lang-change-notification-scoped";
+
+/// Provides a nested `LangIdContext` fixed to `langid`, which shadows the parent context
+/// for the children of the current component, e.g. for an admin panel that is always
+/// rendered in English regardless of the app language.
+///
+/// `use_langid` and `expect_langid` (and thus `rtr!`) resolve to the nearest context.
+/// A scoped context doesn't listen for language change events, so it is unaffected by
+/// `change_langid` calls anywhere in the app, including within its own subtree.
+/// Use the returned setter to change its language instead. To give a subtree its own
+/// persisted and switchable language, call `provide_langid_context_with_event_name`
+/// with a distinct event name in it.
+pub fn provide_scoped_langid_context(
+    langid: i18n::LanguageIdentifier,
+) -> ArcWriteSignal<i18n::LanguageIdentifier> {
+    let langid = ArcRwSignal::new(langid);
+    provide_context(LangIdContext {
+        langid: langid.clone(),
+        event_name: SCOPED_LANGID_CHANGE_EVENT.into(),
+    });
+    langid.write_only()
+}
+
+/// Provides a `LangIdContext` initialized to `langid` without touching `window`
+/// or local storage, for testing components that use `rtr!` outside of a browser.
+///
+/// Returns a setter for the langid, so tests can switch the language and assert
+/// that translations are re-evaluated.
+///
+/// Only available with the `testing` feature.
+#[cfg(feature = "testing")]
+pub fn provide_test_langid(
+    langid: i18n::LanguageIdentifier,
+) -> ArcWriteSignal<i18n::LanguageIdentifier> {
+    let langid = ArcRwSignal::new(langid);
+    provide_context(LangIdContext {
+        langid: langid.clone(),
+        event_name: DEFAULT_LANGID_CHANGE_EVENT.into(),
+    });
+    langid.write_only()
+}
+
+/// Returns whether the context whose langid signal is `langid` was replaced by another one
+/// provided by the same `owner` since, whose listener handles the changes instead, so that
+/// every change is only handled (and persisted) once. Contexts provided by other owners,
+/// e.g. nested or sibling ones, don't replace it and keep listening.
+fn is_replaced_context(
+    owner: Option<&Owner>,
+    langid: &ArcRwSignal<i18n::LanguageIdentifier>,
+) -> bool {
+    owner.is_some_and(|owner| {
+        owner
+            .with(use_context::<LangIdContext>)
+            .is_some_and(|ctx| ctx.langid != *langid)
+    })
+}
+
+/// Reads the langid with `load` and listens for language changes, which are persisted
+/// with `store`. Resets clear the persisted langid and revert to `system_langid`.
+fn setup_persistence_handler(
     langid: ArcRwSignal<i18n::LanguageIdentifier>,
     initial_langid: i18n::LanguageIdentifier,
-    key: String,
+    system_langid: Arc<dyn Fn() -> i18n::LanguageIdentifier>,
+    load: Arc<dyn Fn() -> Option<String>>,
+    store: Arc<dyn Fn(&str)>,
+    event_name: Arc<str>,
+    hydrate: bool,
 ) {
-    // set initial local storage langid
-    if let Ok(Some(storage_langid)) = utils::local_storage::get(&key) {
-        let new_langid =
-            i18n::LanguageIdentifier::from_str(&storage_langid).unwrap_or(initial_langid.clone());
-        langid.set(new_langid);
+    // parses a stored or dispatched langid, rejecting ones the app has no translations for
+    let available = use_available_locales();
+    let parse_langid = move |langid: &str| {
+        notified_langid(langid)
+            .and_then(|langid| clamp_langid(langid, available.as_deref()))
+            .unwrap_or_else(|| initial_langid.clone())
+    };
+
+    // set initial stored langid
+    let read_stored_langid = {
+        let langid = langid.clone();
+        let parse_langid = parse_langid.clone();
+        move || {
+            if let Some(stored_langid) = load().filter(|stored| !stored.is_empty()) {
+                langid.set(parse_langid(&stored_langid));
+            }
+        }
+    };
+    // there is nothing to hydrate outside of the browser
+    if hydrate && cfg!(target_arch = "wasm32") {
+        request_animation_frame(read_stored_langid);
+    } else {
+        read_stored_langid();
     }
 
-    // handle programmatic change of theme
-    let custom_event = leptos::ev::Custom::<leptos::ev::CustomEvent>::new(LANGID_EVENT_CHANGE_NAME);
-    _ = leptos_use::use_event_listener(leptos_use::use_window(), custom_event, {
+    // handle programmatic change of langid
+    let owner = Owner::current();
+    let stop_listening = utils::langid_events::listen(event_name, {
         let langid = langid.clone();
-        let initial_langid = initial_langid.clone();
-        let key = key.clone();
-        move |data| {
-            let new_langid = match data.detail().as_string() {
-                Some(langid) => langid,
-                None => {
-                    log::error!("invalid data passed in the '{LANGID_EVENT_CHANGE_NAME}' event");
-                    return;
-                }
-            };
-            if let Err(err) = utils::local_storage::set(&key, &new_langid) {
-                log::error!("failed to set langid in local storage: {err:?}");
+        move |new_langid| {
+            if is_replaced_context(owner.as_ref(), &langid) {
+                return;
             }
-            langid.set(
-                i18n::LanguageIdentifier::from_str(&new_langid).unwrap_or(initial_langid.clone()),
-            );
+            let new_langid = if new_langid.is_empty() {
+                store("");
+                system_langid()
+            } else {
+                let new_langid = parse_langid(&new_langid);
+                store(&new_langid.to_string());
+                new_langid
+            };
+            langid.set(new_langid);
         }
     });
+
+    // stop listening once the context is unmounted
+    on_cleanup(stop_listening);
 }
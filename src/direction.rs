@@ -0,0 +1,115 @@
+use leptos::prelude::*;
+
+/// The scripts written from right to left.
+const RTL_SCRIPTS: &[&str] = &[
+    "Adlm", "Arab", "Hebr", "Mand", "Nkoo", "Rohg", "Samr", "Syrc", "Thaa",
+];
+
+/// The languages written from right to left when no script is specified.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+
+/// The direction text in a language is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right.
+    Ltr,
+    /// Right to left.
+    Rtl,
+}
+
+impl TextDirection {
+    /// Returns the direction of `langid`, based on its script if specified (e.g. `pa-Arab`
+    /// is RTL while `pa` isn't) and on its language otherwise.
+    pub fn of(langid: &i18n::LanguageIdentifier) -> Self {
+        let is_rtl = match &langid.script {
+            Some(script) => RTL_SCRIPTS.contains(&script.as_str()),
+            None => RTL_LANGUAGES.contains(&langid.language.as_str()),
+        };
+        if is_rtl {
+            Self::Rtl
+        } else {
+            Self::Ltr
+        }
+    }
+
+    /// Returns the direction as the value of the HTML `dir` attribute, `ltr` or `rtl`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+}
+
+/// Returns a signal of the text direction of the active language, e.g. for the `dir`
+/// attribute of the root element. Panics if no `LangIdContext` is provided.
+pub fn use_text_direction() -> Signal<TextDirection> {
+    let langid = crate::expect_langid();
+    Signal::derive(move || langid.with(TextDirection::of))
+}
+
+/// Returns a signal of whether the active language is written from right to left, e.g. for
+/// `class:flip-icon=move || is_rtl.get()`. Panics if no `LangIdContext` is provided.
+pub fn use_is_rtl() -> Signal<bool> {
+    let direction = use_text_direction();
+    Signal::derive(move || direction.get() == TextDirection::Rtl)
+}
+
+/// First strong isolate, which starts a bidi isolated span of text.
+const FSI: char = '\u{2068}';
+
+/// Pop directional isolate, which ends the bidi isolated span started by `FSI`, `LRI` or `RLI`.
+const PDI: char = '\u{2069}';
+
+/// Left-to-right isolate, which starts a bidi isolated span of left-to-right text.
+const LRI: char = '\u{2066}';
+
+/// Right-to-left isolate, which starts a bidi isolated span of right-to-left text.
+const RLI: char = '\u{2067}';
+
+/// How Unicode bidi isolation marks around interpolated values are handled,
+/// see `provide_bidi_isolation`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BidiIsolation {
+    /// Leaves isolation to the Fluent bundles, which isolate placeables unless configured not to.
+    #[default]
+    Bundle,
+    /// Additionally wraps the string arguments of `rtr!` in FSI/PDI marks, so that an RTL
+    /// value in an LTR message (or vice versa) doesn't scramble the surrounding text,
+    /// whatever the bundles are configured to do.
+    Isolate,
+    /// Removes all isolation marks from the values of resolved messages, e.g. for apps
+    /// whose messages mostly end up in `<input>` values or other plain-text sinks where the
+    /// control characters are harmful.
+    Strip,
+}
+
+/// Provides the `BidiIsolation` used by the `rtr!` calls in the current component and its
+/// children, e.g. `BidiIsolation::Isolate` for apps mixing RTL and LTR content.
+///
+/// Use `strip_bidi_isolation` or `ReactiveMessage::value_stripped` to strip the marks of
+/// single values instead, e.g. for the `value` of an `<input>`.
+pub fn provide_bidi_isolation(isolation: BidiIsolation) {
+    provide_context(isolation);
+}
+
+/// Returns the `BidiIsolation` in scope.
+pub(crate) fn use_bidi_isolation() -> BidiIsolation {
+    use_context::<BidiIsolation>().unwrap_or_default()
+}
+
+/// Wraps `value` in FSI/PDI marks.
+pub(crate) fn isolate(value: &str) -> String {
+    format!("{FSI}{value}{PDI}")
+}
+
+/// Removes all bidi isolation marks from `value`, e.g. the ones Fluent inserts around
+/// placeables, for plain-text sinks like the `value` of an `<input>`.
+pub fn strip_bidi_isolation(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(*c, FSI | PDI | LRI | RLI))
+        .collect()
+}
@@ -0,0 +1,122 @@
+use leptos::prelude::untrack;
+use std::cell::RefCell;
+use std::rc::Rc;
+use thiserror::Error;
+
+/// An error encountered while localizing, reported to the handler set with
+/// `set_i18n_error_handler`.
+///
+/// Every error is recovered from (e.g. by falling back to the message ID),
+/// so it is only reported for observability.
+#[derive(Error, Debug, Clone)]
+pub enum I18nError {
+    #[error("failed to resolve message '{id}' for '{langid}': {errors}")]
+    Query {
+        id: String,
+        langid: i18n::LanguageIdentifier,
+        errors: String,
+    },
+    #[error(
+        "message '{id}' for '{langid}' references itself through other messages: {}",
+        .cycle.join(" -> ")
+    )]
+    CyclicReference {
+        id: String,
+        langid: i18n::LanguageIdentifier,
+        cycle: Vec<String>,
+    },
+    #[error("missing translation '{id}' for '{langid}'")]
+    MissingTranslation {
+        id: String,
+        langid: i18n::LanguageIdentifier,
+    },
+    #[error("an error occurred during localization of '{attr}': {error}")]
+    Attribute { attr: String, error: String },
+    #[error("no message could be resolved for '{0}', are its locales loaded?")]
+    LocaleNotLoaded(i18n::LanguageIdentifier),
+    #[error("reactive message signal disposed {0}")]
+    Disposed(String),
+    #[error("{0}")]
+    Format(String),
+    #[error("{0}")]
+    LangId(String),
+    #[error("{0}")]
+    Document(String),
+}
+
+/// An error resolving a message, as returned by `query_message`.
+#[derive(Error, Debug, Clone)]
+pub enum MessageError {
+    /// An error reported by Fluent, e.g. a reference to a missing message or variable.
+    #[error(transparent)]
+    Fluent(#[from] fluent_bundle::FluentError),
+    /// A cyclic reference between messages, as the references along the cycle,
+    /// e.g. `["a", "b", "a"]`.
+    #[error("cyclic reference between messages: {}", .cycle.join(" -> "))]
+    CyclicReference { cycle: Vec<String> },
+    /// An argument of a kind that the variants selected on it can't match, e.g. a string
+    /// for `{ $count -> [one] ... *[other] ... }`, which always selects the default variant.
+    #[error("argument '{arg}' is a {actual}, but a {expected} is needed to select a variant")]
+    ArgumentType {
+        arg: String,
+        expected: &'static str,
+        actual: &'static str,
+    },
+}
+
+thread_local! {
+    static ERROR_HANDLER: RefCell<Option<Rc<dyn Fn(I18nError)>>> = Default::default();
+}
+
+/// Routes the errors of this crate to `handler` instead of logging them, e.g. to report
+/// them through `tracing` or an error tracking service. Replaces any previous handler.
+///
+/// The handler runs untracked, so that signals it reads don't make the failed lookup re-run.
+/// Without a handler, errors are logged with `log::error!`, except failed message lookups,
+/// which fall back to the message ID and are only logged with `log::debug!`, unless they
+/// fail on a cyclic reference between messages. Missing translations recorded with the
+/// `dev-warnings` feature are logged with `log::warn!`.
+/// With the `quiet` feature, they are dropped instead, which compiles the logging out.
+pub fn set_i18n_error_handler(handler: impl Fn(I18nError) + 'static) {
+    ERROR_HANDLER.with_borrow_mut(|current| *current = Some(Rc::new(handler)));
+}
+
+/// Builds the error of a failed lookup of `id` in `langid`, telling cyclic message references
+/// apart from other failures.
+pub(crate) fn query_error(
+    id: &str,
+    langid: &i18n::LanguageIdentifier,
+    errors: &[MessageError],
+) -> I18nError {
+    let (id, langid) = (id.to_string(), langid.clone());
+    let cycle = errors.iter().find_map(|err| match err {
+        MessageError::CyclicReference { cycle } => Some(cycle.clone()),
+        _ => None,
+    });
+    match cycle {
+        Some(cycle) => I18nError::CyclicReference { id, langid, cycle },
+        None => I18nError::Query {
+            id,
+            langid,
+            errors: format!("{errors:?}"),
+        },
+    }
+}
+
+/// Passes `err` to the error handler, or logs it if there is none.
+pub(crate) fn report_error(err: I18nError) {
+    // clone the handler out, so that it can replace itself
+    match ERROR_HANDLER.with_borrow(|handler| handler.clone()) {
+        Some(handler) => untrack(|| handler(err)),
+        #[cfg(not(feature = "quiet"))]
+        None if matches!(err, I18nError::Query { .. }) => log::debug!("i18n_leptos | {err}"),
+        #[cfg(not(feature = "quiet"))]
+        None if matches!(err, I18nError::MissingTranslation { .. }) => {
+            log::warn!("i18n_leptos | {err}")
+        }
+        #[cfg(not(feature = "quiet"))]
+        None => log::error!("i18n_leptos | {err}"),
+        #[cfg(feature = "quiet")]
+        None => {}
+    }
+}
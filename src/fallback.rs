@@ -0,0 +1,54 @@
+use leptos::prelude::*;
+use std::sync::Arc;
+
+/// Determines the value of a message whose lookup failed.
+#[derive(Clone, Default)]
+pub enum FallbackPolicy {
+    /// Uses the message ID, e.g. `welcome-message`.
+    #[default]
+    Id,
+    /// Uses an empty string, so missing translations are invisible.
+    Empty,
+    /// Uses the message ID with the given prefix, e.g. `⚠️welcome-message`.
+    Prefixed(String),
+    /// Uses the value returned for the message ID and the active langid.
+    Custom(Arc<dyn Fn(&str, &i18n::LanguageIdentifier) -> String + Send + Sync>),
+}
+
+impl FallbackPolicy {
+    /// A `FallbackPolicy::Custom` calling `f`.
+    pub fn custom(
+        f: impl Fn(&str, &i18n::LanguageIdentifier) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// Returns the value of the message `id` whose lookup failed for `langid`.
+    pub(crate) fn fallback_value(&self, id: &str, langid: &i18n::LanguageIdentifier) -> String {
+        match self {
+            Self::Id => id.to_string(),
+            Self::Empty => String::new(),
+            Self::Prefixed(prefix) => format!("{prefix}{id}"),
+            Self::Custom(f) => f(id, langid),
+        }
+    }
+}
+
+impl std::fmt::Debug for FallbackPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id => write!(f, "Id"),
+            Self::Empty => write!(f, "Empty"),
+            Self::Prefixed(prefix) => f.debug_tuple("Prefixed").field(prefix).finish(),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Provides the `FallbackPolicy` used by `rtr!` and the other lookups of the current
+/// component and its children when a message can't be resolved.
+///
+/// Without one, failed lookups fall back to the message ID.
+pub fn provide_fallback_policy(policy: FallbackPolicy) {
+    provide_context(policy);
+}
@@ -0,0 +1,103 @@
+//! Loading FTL files over HTTP in the browser.
+
+use crate::LoadLocaleError;
+use leptos::prelude::*;
+use std::rc::Rc;
+use thiserror::Error;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::Response;
+
+/// The placeholder in URL patterns that is replaced by the langid, e.g. `/locales/{lang}/main.ftl`.
+const LANG_PLACEHOLDER: &str = "{lang}";
+
+/// An error returned by `fetch_ftl` and `load_best_locale_from_url`.
+#[derive(Error, Debug, Clone)]
+pub enum FetchFtlError {
+    #[error("failed to fetch '{url}': {error}")]
+    Network { url: String, error: String },
+    #[error("failed to fetch '{url}': HTTP status {status}")]
+    Http { url: String, status: u16 },
+    #[error("failed to parse '{url}': {error}")]
+    Parse { url: String, error: String },
+}
+
+/// Describes an exception thrown by the browser.
+fn js_error(err: JsValue) -> String {
+    err.as_string().unwrap_or_else(|| format!("{err:?}"))
+}
+
+/// Returns the URL of the FTL file of `langid`, replacing `{lang}` in `pattern`,
+/// e.g. `/locales/fr/main.ftl` for `/locales/{lang}/main.ftl`.
+pub fn ftl_url(pattern: &str, langid: &i18n::LanguageIdentifier) -> String {
+    pattern.replace(LANG_PLACEHOLDER, &langid.to_string())
+}
+
+/// Fetches the text of the FTL file at `url`.
+///
+/// Responses with a status outside of `200-299` fail with `FetchFtlError::Http`, so that
+/// an HTML error page is never parsed as FTL.
+pub async fn fetch_ftl(url: &str) -> Result<String, FetchFtlError> {
+    let network_error = |err| FetchFtlError::Network {
+        url: url.to_string(),
+        error: js_error(err),
+    };
+
+    let response = wasm_bindgen_futures::JsFuture::from(window().fetch_with_str(url))
+        .await
+        .map_err(network_error)?;
+    let response: Response = response.dyn_into().map_err(network_error)?;
+    if !response.ok() {
+        return Err(FetchFtlError::Http {
+            url: url.to_string(),
+            status: response.status(),
+        });
+    }
+
+    let text = response.text().map_err(network_error)?;
+    let text = wasm_bindgen_futures::JsFuture::from(text)
+        .await
+        .map_err(network_error)?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+/// Like `load_best_locale`, but fetches the FTL file of the best matching locale from the
+/// URL given by `url_pattern`, in which `{lang}` is replaced by the langid.
+///
+/// The fetched FTL source is turned into `Locales` by `parse`, whose errors are reported as
+/// `FetchFtlError::Parse`, while failed requests are reported as `FetchFtlError::Network`
+/// or `FetchFtlError::Http`. On success, the locales are added with `add_locales` and the
+/// langid is set to the loaded one.
+///
+/// ```ignore
+/// let locale = load_best_locale_from_url(
+///     available_locales(),
+///     "/locales/{lang}/main.ftl",
+///     |langid, ftl| build_locales(langid, ftl),
+/// );
+/// ```
+pub fn load_best_locale_from_url<F, E>(
+    available: &[i18n::LanguageIdentifier],
+    url_pattern: &str,
+    parse: F,
+) -> LocalResource<Result<i18n::LanguageIdentifier, LoadLocaleError<FetchFtlError>>>
+where
+    F: Fn(&i18n::LanguageIdentifier, String) -> Result<i18n::Locales, E> + 'static,
+    E: std::fmt::Display,
+{
+    let url_pattern = url_pattern.to_string();
+    let parse = Rc::new(parse);
+
+    crate::load_best_locale(available, move |langid| {
+        let url = ftl_url(&url_pattern, &langid);
+        let parse = parse.clone();
+        async move {
+            let ftl = fetch_ftl(&url).await?;
+            let locales = parse(&langid, ftl).map_err(|err| FetchFtlError::Parse {
+                url,
+                error: err.to_string(),
+            })?;
+            // the locales are added for the rest of the app's lifetime
+            Ok(&*Box::leak(Box::new(locales)))
+        }
+    })
+}
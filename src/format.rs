@@ -0,0 +1,354 @@
+//! Locale-aware formatting helpers backed by the browser's `Intl` API.
+
+use leptos::prelude::*;
+use std::cmp::Ordering;
+use web_sys::js_sys;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+
+/// Returns `langid` as a locales argument for the `Intl` constructors.
+fn intl_locales(langid: &i18n::LanguageIdentifier) -> js_sys::Array {
+    js_sys::Array::of1(&JsValue::from_str(&langid.to_string()))
+}
+
+/// Sets `key` to `value` on an `Intl` options object.
+fn set_option(options: &js_sys::Object, key: &str, value: impl Into<JsValue>) {
+    _ = js_sys::Reflect::set(options, &JsValue::from_str(key), &value.into());
+}
+
+/// The units used for relative time formatting along with their length in milliseconds,
+/// from the largest to the smallest.
+const RELATIVE_TIME_UNITS: &[(&str, f64)] = &[
+    ("year", 365.0 * 24.0 * 60.0 * 60.0 * 1000.0),
+    ("month", 30.0 * 24.0 * 60.0 * 60.0 * 1000.0),
+    ("week", 7.0 * 24.0 * 60.0 * 60.0 * 1000.0),
+    ("day", 24.0 * 60.0 * 60.0 * 1000.0),
+    ("hour", 60.0 * 60.0 * 1000.0),
+    ("minute", 60.0 * 1000.0),
+    ("second", 1000.0),
+];
+
+/// Formats the distance from `now` to `instant` as a relative phrase in the active
+/// language, e.g. "3 minutes ago" or "in 2 days", picking the largest fitting unit.
+///
+/// Both timestamps are milliseconds since the Unix epoch, as returned by `js_sys::Date::now()`.
+/// Formatting is done by the browser's `Intl.RelativeTimeFormat`, which handles
+/// past/future and plural forms, and is re-run when the language changes.
+pub fn format_relative_time(instant: f64, now: f64) -> Signal<String> {
+    let langid = crate::expect_langid();
+    Signal::derive(move || {
+        let diff = instant - now;
+        let (unit, unit_ms) = RELATIVE_TIME_UNITS
+            .iter()
+            .copied()
+            .find(|(_, unit_ms)| diff.abs() >= *unit_ms)
+            .unwrap_or(("second", 1000.0));
+
+        let options = js_sys::Object::new();
+        set_option(&options, "numeric", "auto");
+        js_sys::Intl::RelativeTimeFormat::new(&intl_locales(&langid.get()), &options)
+            .format((diff / unit_ms).round(), unit)
+            .into()
+    })
+}
+
+/// Options for formatting numbers, mirroring the ones of `Intl.NumberFormat`.
+/// Unset options use the locale's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberFormatOptions {
+    /// The minimum number of fraction digits to display.
+    pub min_fraction_digits: Option<u8>,
+    /// The maximum number of fraction digits to display.
+    pub max_fraction_digits: Option<u8>,
+    /// Whether to display grouping separators, such as thousands separators.
+    pub use_grouping: Option<bool>,
+}
+
+/// Formats `value` with an `Intl.NumberFormat` for `langid`, as a currency if `currency` is set.
+fn format_number_in(
+    langid: &i18n::LanguageIdentifier,
+    value: f64,
+    currency: Option<&str>,
+    options: &NumberFormatOptions,
+) -> String {
+    let intl_options = js_sys::Object::new();
+    if let Some(currency) = currency {
+        set_option(&intl_options, "style", "currency");
+        set_option(&intl_options, "currency", currency);
+    }
+    if let Some(digits) = options.min_fraction_digits {
+        set_option(&intl_options, "minimumFractionDigits", digits);
+    }
+    if let Some(digits) = options.max_fraction_digits {
+        set_option(&intl_options, "maximumFractionDigits", digits);
+    }
+    if let Some(use_grouping) = options.use_grouping {
+        set_option(&intl_options, "useGrouping", use_grouping);
+    }
+
+    js_sys::Intl::NumberFormat::new(&intl_locales(langid), &intl_options)
+        .format()
+        .call1(&JsValue::NULL, &JsValue::from_f64(value))
+        .ok()
+        .and_then(|formatted| formatted.as_string())
+        .unwrap_or_else(|| {
+            crate::error::report_error(crate::I18nError::Format(format!(
+                "failed to format number '{value}' for '{langid}'"
+            )));
+            value.to_string()
+        })
+}
+
+/// Formats `value` according to the active language's grouping separators and decimal mark.
+///
+/// This is re-run when the language changes.
+pub fn format_number(value: f64) -> Signal<String> {
+    format_number_with(value, NumberFormatOptions::default())
+}
+
+/// Like `format_number`, but with explicit formatting options.
+pub fn format_number_with(value: f64, options: NumberFormatOptions) -> Signal<String> {
+    let langid = crate::expect_langid();
+    Signal::derive(move || format_number_in(&langid.get(), value, None, &options))
+}
+
+/// Formats `value` as an amount of the currency with the ISO 4217 `currency_code` (e.g. `"EUR"`),
+/// placing the currency symbol the way the active language does.
+///
+/// This is re-run when the language changes.
+pub fn format_currency(value: f64, currency_code: &str) -> Signal<String> {
+    format_currency_with(value, currency_code, NumberFormatOptions::default())
+}
+
+/// Like `format_currency`, but with explicit formatting options.
+pub fn format_currency_with(
+    value: f64,
+    currency_code: &str,
+    options: NumberFormatOptions,
+) -> Signal<String> {
+    let langid = crate::expect_langid();
+    let currency_code = currency_code.to_string();
+    Signal::derive(move || format_number_in(&langid.get(), value, Some(&currency_code), &options))
+}
+
+/// Compares `a` and `b` with the `compare` function of an `Intl.Collator`,
+/// falling back to `str::cmp` if the call fails.
+fn collator_compare(compare: &js_sys::Function, a: &str, b: &str) -> Ordering {
+    compare
+        .call2(&JsValue::NULL, &JsValue::from_str(a), &JsValue::from_str(b))
+        .ok()
+        .and_then(|ordering| ordering.as_f64())
+        .and_then(|ordering| ordering.partial_cmp(&0.0))
+        .unwrap_or_else(|| a.cmp(b))
+}
+
+/// Returns the `compare` function of an `Intl.Collator` for the active language.
+fn active_collator_compare() -> js_sys::Function {
+    let langid = crate::expect_langid().get();
+    js_sys::Intl::Collator::new(&intl_locales(&langid), &js_sys::Object::new()).compare()
+}
+
+/// Compares two strings according to the collation rules of the active language,
+/// so that e.g. "Égypte" sorts next to "Egypte" for a French user instead of after "Zambie".
+///
+/// Collation is done by the browser's `Intl.Collator`, so no ICU data is bundled.
+/// This is a reactive read of the language.
+pub fn locale_compare(a: &str, b: &str) -> Ordering {
+    collator_compare(&active_collator_compare(), a, b)
+}
+
+/// Sorts `items` according to the collation rules of the active language, see `locale_compare`.
+///
+/// This is a reactive read of the language.
+pub fn sort_by_locale(items: &mut [String]) {
+    let compare = active_collator_compare();
+    items.sort_by(|a, b| collator_compare(&compare, a, b));
+}
+
+/// The kind of list formatted by `format_list`, mirroring the `type` option of `Intl.ListFormat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListStyle {
+    /// A list of items that all apply, e.g. "apples, oranges, and pears".
+    #[default]
+    Conjunction,
+    /// A list of alternatives, e.g. "apples, oranges, or pears".
+    Disjunction,
+    /// A list of measurements, e.g. "5 pounds, 12 ounces".
+    Unit,
+}
+
+impl ListStyle {
+    fn as_str(self) -> &'static str {
+        match self {
+            ListStyle::Conjunction => "conjunction",
+            ListStyle::Disjunction => "disjunction",
+            ListStyle::Unit => "unit",
+        }
+    }
+}
+
+/// Formats `items` with an `Intl.ListFormat` for `langid`.
+///
+/// `js_sys` has no bindings for `Intl.ListFormat`, so it is looked up dynamically.
+fn format_list_in(
+    langid: &i18n::LanguageIdentifier,
+    items: &[String],
+    style: ListStyle,
+) -> Result<String, JsValue> {
+    let intl = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("Intl"))?;
+    let list_format: js_sys::Function =
+        js_sys::Reflect::get(&intl, &JsValue::from_str("ListFormat"))?.dyn_into()?;
+
+    let options = js_sys::Object::new();
+    set_option(&options, "type", style.as_str());
+    let list_format = js_sys::Reflect::construct(
+        &list_format,
+        &js_sys::Array::of2(&intl_locales(langid), &options),
+    )?;
+
+    let format: js_sys::Function =
+        js_sys::Reflect::get(&list_format, &JsValue::from_str("format"))?.dyn_into()?;
+    let items: js_sys::Array = items.iter().map(|item| JsValue::from_str(item)).collect();
+    format
+        .call1(&list_format, &items)?
+        .as_string()
+        .ok_or_else(|| JsValue::from_str("Intl.ListFormat returned a non-string value"))
+}
+
+/// Formats `items` as a list in the active language, e.g. "apples, oranges, and pears"
+/// in English or "manzanas, naranjas y peras" in Spanish.
+///
+/// Formatting is done by the browser's `Intl.ListFormat` and is re-run when the language changes.
+/// If it isn't supported, the items are joined with commas.
+pub fn format_list(items: &[String], style: ListStyle) -> Signal<String> {
+    let langid = crate::expect_langid();
+    let items = items.to_vec();
+    Signal::derive(move || {
+        let langid = langid.get();
+        format_list_in(&langid, &items, style).unwrap_or_else(|err| {
+            crate::error::report_error(crate::I18nError::Format(format!(
+                "failed to format list for '{langid}': {err:?}"
+            )));
+            items.join(", ")
+        })
+    })
+}
+
+/// The length of a formatted date or time, mirroring the `dateStyle` and `timeStyle` options
+/// of `Intl.DateTimeFormat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateTimeStyle {
+    /// The most compact form, e.g. "1/5/25" or "3:30 PM".
+    Short,
+    /// e.g. "Jan 5, 2025" or "3:30:00 PM".
+    #[default]
+    Medium,
+    /// e.g. "January 5, 2025" or "3:30:00 PM UTC".
+    Long,
+    /// The most detailed form, e.g. "Sunday, January 5, 2025"
+    /// or "3:30:00 PM Coordinated Universal Time".
+    Full,
+}
+
+impl DateTimeStyle {
+    fn as_str(self) -> &'static str {
+        match self {
+            DateTimeStyle::Short => "short",
+            DateTimeStyle::Medium => "medium",
+            DateTimeStyle::Long => "long",
+            DateTimeStyle::Full => "full",
+        }
+    }
+}
+
+/// Formats `timestamp` with an `Intl.DateTimeFormat` for `langid`, setting the style
+/// option `style_key` (`dateStyle` or `timeStyle`) to `style`.
+fn format_date_time_in(
+    langid: &i18n::LanguageIdentifier,
+    timestamp: f64,
+    style_key: &str,
+    style: DateTimeStyle,
+) -> String {
+    let options = js_sys::Object::new();
+    set_option(&options, style_key, style.as_str());
+    let date = js_sys::Date::new(&JsValue::from_f64(timestamp));
+
+    js_sys::Intl::DateTimeFormat::new(&intl_locales(langid), &options)
+        .format()
+        .call1(&JsValue::NULL, &date)
+        .ok()
+        .and_then(|formatted| formatted.as_string())
+        .unwrap_or_else(|| {
+            crate::error::report_error(crate::I18nError::Format(format!(
+                "failed to format timestamp '{timestamp}' for '{langid}'"
+            )));
+            timestamp.to_string()
+        })
+}
+
+/// Formats the date of `timestamp` in the active language, e.g. "January 5, 2025" in English
+/// or "5 janvier 2025" in French with `DateTimeStyle::Long`.
+///
+/// `timestamp` is in milliseconds since the Unix epoch, as returned by `js_sys::Date::now()`,
+/// and is shown in the browser's local time zone. Formatting is done by the browser's
+/// `Intl.DateTimeFormat`, so no ICU data is bundled, and is re-run when the language changes.
+/// If formatting fails, e.g. for an invalid timestamp, the raw timestamp is shown instead.
+pub fn format_date(timestamp: f64, style: DateTimeStyle) -> Signal<String> {
+    let langid = crate::expect_langid();
+    Signal::derive(move || format_date_time_in(&langid.get(), timestamp, "dateStyle", style))
+}
+
+/// Formats the time of day of `timestamp` in the active language, e.g. "3:30 PM" in English
+/// or "15:30" in French with `DateTimeStyle::Short`.
+///
+/// Like `format_date`, the time is shown in the browser's local time zone,
+/// and formatting is re-run when the language changes.
+pub fn format_time(timestamp: f64, style: DateTimeStyle) -> Signal<String> {
+    let langid = crate::expect_langid();
+    Signal::derive(move || format_date_time_in(&langid.get(), timestamp, "timeStyle", style))
+}
+
+/// Looks up the name of `langid` in `in_langid` with an `Intl.DisplayNames`.
+///
+/// `js_sys` has no bindings for `Intl.DisplayNames`, so it is looked up dynamically.
+fn display_name_in(
+    langid: &i18n::LanguageIdentifier,
+    in_langid: &i18n::LanguageIdentifier,
+) -> Result<Option<String>, JsValue> {
+    let intl = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("Intl"))?;
+    let display_names: js_sys::Function =
+        js_sys::Reflect::get(&intl, &JsValue::from_str("DisplayNames"))?.dyn_into()?;
+
+    let options = js_sys::Object::new();
+    set_option(&options, "type", "language");
+    // return `undefined` instead of the code for unknown languages
+    set_option(&options, "fallback", "none");
+    let display_names = js_sys::Reflect::construct(
+        &display_names,
+        &js_sys::Array::of2(&intl_locales(in_langid), &options),
+    )?;
+
+    let of: js_sys::Function =
+        js_sys::Reflect::get(&display_names, &JsValue::from_str("of"))?.dyn_into()?;
+    Ok(of
+        .call1(&display_names, &JsValue::from_str(&langid.to_string()))?
+        .as_string())
+}
+
+/// Returns the name of the language `langid` in the language `in_langid`, e.g. "French"
+/// for `fr` in `en`, or its autonym if both are the same, e.g. "Français" for a language
+/// switcher listing every language in itself.
+///
+/// Names are looked up with the browser's `Intl.DisplayNames`, so no table of names is
+/// bundled. Returns `None` if the browser has no name for `langid` in `in_langid`, or
+/// doesn't support `Intl.DisplayNames`, in which case callers typically show the langid itself.
+pub fn display_name(
+    langid: &i18n::LanguageIdentifier,
+    in_langid: &i18n::LanguageIdentifier,
+) -> Option<String> {
+    display_name_in(langid, in_langid).unwrap_or_else(|err| {
+        crate::error::report_error(crate::I18nError::Format(format!(
+            "failed to look up the display name of '{langid}' in '{in_langid}': {err:?}"
+        )));
+        None
+    })
+}
@@ -0,0 +1,61 @@
+//! Helpers for localizing the document title and meta tags.
+
+use crate::ReactiveMessage;
+use leptos::prelude::*;
+
+/// Keeps `document.title` set to the value of `msg`, updating it whenever the translation
+/// changes, e.g. `use_localized_title(rtr!("about-page-title"))`.
+///
+/// The title is no longer updated once the current owner is cleaned up.
+pub fn use_localized_title(msg: ReactiveMessage) {
+    Effect::new(move |_| document().set_title(&msg.value()));
+}
+
+/// Keeps the `content` of the meta tag `name` set to the value of `msg`, updating it
+/// whenever the translation changes, e.g. `use_localized_meta("description", msg)`.
+///
+/// The tag is looked up in the `<head>` by its `name` attribute or, for Open Graph tags like
+/// `og:title`, its `property` attribute. If there is no such tag, it is added to the `<head>`.
+pub fn use_localized_meta(name: &str, msg: ReactiveMessage) {
+    let name = name.to_string();
+    Effect::new(move |_| {
+        let value = msg.value();
+        let Some(meta) = find_or_create_meta(&name) else {
+            crate::error::report_error(crate::I18nError::Document(format!(
+                "failed to create the '{name}' meta tag"
+            )));
+            return;
+        };
+        _ = meta.set_attribute("content", &value);
+    });
+}
+
+/// Returns the meta tag `name` in the `<head>`, adding it if there is none.
+///
+/// The tags are compared by attribute rather than with a selector, so that names which
+/// aren't valid in a CSS selector, e.g. with quotes or brackets, can't add duplicate tags.
+fn find_or_create_meta(name: &str) -> Option<web_sys::Element> {
+    let document = document();
+    let head = document.head()?;
+    let mut child = head.first_element_child();
+    while let Some(element) = child {
+        let is_meta = element.tag_name().eq_ignore_ascii_case("meta");
+        let has_name = ["name", "property"]
+            .iter()
+            .any(|attr| element.get_attribute(attr).as_deref() == Some(name));
+        if is_meta && has_name {
+            return Some(element);
+        }
+        child = element.next_element_sibling();
+    }
+
+    let meta = document.create_element("meta").ok()?;
+    let attr = if name.starts_with("og:") {
+        "property"
+    } else {
+        "name"
+    };
+    meta.set_attribute(attr, name).ok()?;
+    head.append_child(&meta).ok()?;
+    Some(meta)
+}
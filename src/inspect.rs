@@ -0,0 +1,374 @@
+use crate::MessageError;
+use fluent_bundle::resolver::ResolverError;
+use fluent_bundle::{FluentError, FluentResource};
+use fluent_syntax::ast;
+use std::collections::{HashMap, HashSet};
+
+/// The messages, terms and attributes referenced by each message, term and attribute
+/// of a bundle, named like `welcome`, `-brand` or `menu.title`.
+type References = HashMap<String, Vec<String>>;
+
+/// Converts the errors of a failed lookup of `id` in the bundle of `langid`, naming the
+/// references along the cycle of a cyclic reference, which Fluent reports without details.
+pub(crate) fn message_errors(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    errors: impl IntoIterator<Item = FluentError>,
+) -> Vec<MessageError> {
+    message_errors_in(&crate::locales::resources(locales, langid), id, errors)
+}
+
+/// Like `message_errors`, but for the bundle made of `resources`.
+fn message_errors_in(
+    resources: &[&FluentResource],
+    id: &str,
+    errors: impl IntoIterator<Item = FluentError>,
+) -> Vec<MessageError> {
+    errors
+        .into_iter()
+        .map(|err| match err {
+            FluentError::ResolverError(ResolverError::Cyclic) => MessageError::CyclicReference {
+                cycle: find_cycle(resources, id)
+                    .unwrap_or_else(|| vec![id.to_string(), id.to_string()]),
+            },
+            err => MessageError::Fluent(err),
+        })
+        .collect()
+}
+
+/// Returns an error for every argument of `args` that the message `id` selects a plural
+/// category or number on, but that is a string, which Fluent silently resolves to the
+/// default variant.
+pub(crate) fn argument_errors(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    args: &i18n::FluentArgs,
+) -> Vec<MessageError> {
+    argument_errors_in(&crate::locales::resources(locales, langid), id, args)
+}
+
+/// Like `argument_errors`, but for the bundle made of `resources`.
+fn argument_errors_in(
+    resources: &[&FluentResource],
+    id: &str,
+    args: &i18n::FluentArgs,
+) -> Vec<MessageError> {
+    let value = resources
+        .iter()
+        .flat_map(|resource| resource.entries())
+        .find_map(|entry| match entry {
+            ast::Entry::Message(msg) if msg.id.name == id => Some(msg.value.as_ref()),
+            _ => None,
+        })
+        .flatten();
+    let Some(value) = value else {
+        return Vec::new();
+    };
+
+    let mut selectors = HashSet::new();
+    add_numeric_selectors(value, &mut selectors);
+    args.iter()
+        .filter(|(name, value)| {
+            selectors.contains(name) && matches!(value, i18n::FluentValue::String(_))
+        })
+        .map(|(name, _)| MessageError::ArgumentType {
+            arg: name.to_string(),
+            expected: "number",
+            actual: "string",
+        })
+        .collect()
+}
+
+/// Adds the variables of `pattern` that a plural category or number is selected on.
+fn add_numeric_selectors<'a>(pattern: &ast::Pattern<&'a str>, selectors: &mut HashSet<&'a str>) {
+    for element in &pattern.elements {
+        let ast::PatternElement::Placeable { expression } = element else {
+            continue;
+        };
+        let ast::Expression::Select { selector, variants } = expression else {
+            continue;
+        };
+        if let ast::InlineExpression::VariableReference { id } = selector {
+            if variants
+                .iter()
+                .any(|variant| is_numeric_variant_key(&variant.key))
+            {
+                selectors.insert(id.name);
+            }
+        }
+        for variant in variants {
+            add_numeric_selectors(&variant.value, selectors);
+        }
+    }
+}
+
+/// Returns whether the variant key `key` is a CLDR plural category other than `other`,
+/// or a number, which only numbers select.
+fn is_numeric_variant_key(key: &ast::VariantKey<&str>) -> bool {
+    match key {
+        ast::VariantKey::Identifier { name } => {
+            matches!(*name, "zero" | "one" | "two" | "few" | "many")
+        }
+        ast::VariantKey::NumberLiteral { .. } => true,
+    }
+}
+
+/// Returns a cycle of references the message `id` depends on in the bundle made of
+/// `resources`, as the names along the cycle, e.g. `["a", "b", "a"]`.
+fn find_cycle(resources: &[&FluentResource], id: &str) -> Option<Vec<String>> {
+    let references = references(resources);
+    find_cycle_from(&references, id, &mut Vec::new(), &mut HashSet::new())
+}
+
+fn find_cycle_from<'a>(
+    references: &'a References,
+    id: &'a str,
+    path: &mut Vec<&'a str>,
+    visited: &mut HashSet<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|other| *other == id) {
+        let mut cycle: Vec<_> = path[start..].iter().map(|id| id.to_string()).collect();
+        cycle.push(id.to_string());
+        return Some(cycle);
+    }
+    if !visited.insert(id) {
+        return None;
+    }
+    let (id, refs) = references.get_key_value(id)?;
+    path.push(id);
+    for reference in refs {
+        if let Some(cycle) = find_cycle_from(references, reference, path, visited) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    None
+}
+
+/// Returns the references of every message and term of the bundle made of `resources`.
+/// Like in Fluent, the first definition of an entry wins.
+fn references(resources: &[&FluentResource]) -> References {
+    let mut references = References::new();
+    for resource in resources {
+        for entry in resource.entries() {
+            let (id, value, attributes) = match entry {
+                ast::Entry::Message(msg) => {
+                    (msg.id.name.to_string(), msg.value.as_ref(), &msg.attributes)
+                }
+                ast::Entry::Term(term) => (
+                    format!("-{}", term.id.name),
+                    Some(&term.value),
+                    &term.attributes,
+                ),
+                _ => continue,
+            };
+            if let Some(value) = value {
+                references
+                    .entry(id.clone())
+                    .or_insert_with(|| pattern_references(value));
+            }
+            for attr in attributes {
+                references
+                    .entry(format!("{id}.{}", attr.id.name))
+                    .or_insert_with(|| pattern_references(&attr.value));
+            }
+        }
+    }
+    references
+}
+
+fn pattern_references(pattern: &ast::Pattern<&str>) -> Vec<String> {
+    let mut refs = Vec::new();
+    add_pattern_references(pattern, &mut refs);
+    refs
+}
+
+fn add_pattern_references(pattern: &ast::Pattern<&str>, refs: &mut Vec<String>) {
+    for element in &pattern.elements {
+        if let ast::PatternElement::Placeable { expression } = element {
+            add_expression_references(expression, refs);
+        }
+    }
+}
+
+fn add_expression_references(expression: &ast::Expression<&str>, refs: &mut Vec<String>) {
+    match expression {
+        ast::Expression::Select { selector, variants } => {
+            add_inline_references(selector, refs);
+            for variant in variants {
+                add_pattern_references(&variant.value, refs);
+            }
+        }
+        ast::Expression::Inline(inline) => add_inline_references(inline, refs),
+    }
+}
+
+fn add_inline_references(inline: &ast::InlineExpression<&str>, refs: &mut Vec<String>) {
+    match inline {
+        ast::InlineExpression::MessageReference { id, attribute } => {
+            refs.push(reference_name("", id, attribute.as_ref()));
+        }
+        ast::InlineExpression::TermReference {
+            id,
+            attribute,
+            arguments,
+        } => {
+            refs.push(reference_name("-", id, attribute.as_ref()));
+            if let Some(arguments) = arguments {
+                add_call_references(arguments, refs);
+            }
+        }
+        ast::InlineExpression::FunctionReference { arguments, .. } => {
+            add_call_references(arguments, refs);
+        }
+        ast::InlineExpression::Placeable { expression } => {
+            add_expression_references(expression, refs);
+        }
+        _ => {}
+    }
+}
+
+/// Returns the name of a reference to the entry `id` or its attribute `attr`.
+fn reference_name(
+    prefix: &str,
+    id: &ast::Identifier<&str>,
+    attr: Option<&ast::Identifier<&str>>,
+) -> String {
+    match attr {
+        Some(attr) => format!("{prefix}{}.{}", id.name, attr.name),
+        None => format!("{prefix}{}", id.name),
+    }
+}
+
+fn add_call_references(arguments: &ast::CallArguments<&str>, refs: &mut Vec<String>) {
+    for arg in &arguments.positional {
+        add_inline_references(arg, refs);
+    }
+    for arg in &arguments.named {
+        add_inline_references(&arg.value, refs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(source: &str) -> FluentResource {
+        FluentResource::try_new(source.to_string()).expect("valid FTL")
+    }
+
+    #[test]
+    fn cycles_are_named_by_their_references() {
+        let resource = resource(
+            "start = { a }\na = A { b }\nb = B { -c }\n-c = C { a }\nsafe = { start.title }\n",
+        );
+        assert_eq!(
+            find_cycle(&[&resource], "start"),
+            Some(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "-c".to_string(),
+                "a".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn attributes_referencing_their_message_arent_cycles() {
+        let resource = resource("menu = { menu.title }\n    .title = Menu\n");
+        assert_eq!(find_cycle(&[&resource], "menu"), None);
+
+        let resource = resource("menu = Menu\n    .title = { menu.title }\n");
+        assert_eq!(
+            find_cycle(&[&resource], "menu.title"),
+            Some(vec!["menu.title".to_string(), "menu.title".to_string()])
+        );
+    }
+
+    #[test]
+    fn cycles_are_searched_through_selectors_and_arguments() {
+        let resource =
+            resource("a = { $n ->\n    [one] One\n   *[other] { NUMBER(b) }\n}\nb = { a }\n");
+        assert_eq!(
+            find_cycle(&[&resource], "a"),
+            Some(vec!["a".to_string(), "b".to_string(), "a".to_string()])
+        );
+    }
+
+    #[test]
+    fn strings_selecting_plural_categories_are_reported() {
+        let resource = resource(
+            "items = { $count ->\n    [one] One item\n   *[other] { $count } items\n} for { $name }\n",
+        );
+        let mut args = i18n::FluentArgs::new();
+        args.set("count", "5");
+        args.set("name", "Ana");
+
+        let errors = argument_errors_in(&[&resource], "items", &args);
+        assert!(
+            matches!(
+                &errors[..],
+                [MessageError::ArgumentType { arg, expected: "number", actual: "string" }]
+                    if arg == "count"
+            ),
+            "{errors:?}"
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "argument 'count' is a string, but a number is needed to select a variant"
+        );
+    }
+
+    #[test]
+    fn only_strings_selecting_numbers_are_reported() {
+        let resource = resource(
+            "items = { $count ->\n    [one] One item\n   *[other] { $count } items\n}\n\
+             greeting = { $gender ->\n    [female] Her\n   *[other] Their\n}\n\
+             nested = { $kind ->\n    [a] { $n ->\n        [0] None\n       *[other] Some\n    }\n   *[other] Other\n}\n",
+        );
+        let mut args = i18n::FluentArgs::new();
+        args.set("count", 5);
+        args.set("gender", "female");
+        assert!(argument_errors_in(&[&resource], "items", &args).is_empty());
+        assert!(argument_errors_in(&[&resource], "greeting", &args).is_empty());
+
+        let mut args = i18n::FluentArgs::new();
+        args.set("kind", "a");
+        args.set("n", "0");
+        let errors = argument_errors_in(&[&resource], "nested", &args);
+        assert!(
+            matches!(&errors[..], [MessageError::ArgumentType { arg, .. }] if arg == "n"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn cyclic_resolver_errors_name_the_cycle() {
+        let resource = resource("a = { b }\nb = { a }\nc = { $x }\n");
+        let errors = message_errors_in(
+            &[&resource],
+            "a",
+            [FluentError::ResolverError(ResolverError::Cyclic)],
+        );
+        assert!(
+            matches!(&errors[..], [MessageError::CyclicReference { cycle }] if cycle == &["a", "b", "a"]),
+            "{errors:?}"
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "cyclic reference between messages: a -> b -> a"
+        );
+
+        let errors = message_errors_in(
+            &[&resource],
+            "c",
+            [FluentError::ResolverError(ResolverError::MissingDefault)],
+        );
+        assert!(
+            matches!(&errors[..], [MessageError::Fluent(_)]),
+            "{errors:?}"
+        );
+    }
+}
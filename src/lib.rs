@@ -24,14 +24,76 @@
 //! is provided in the Leptos context (e.g., via `i18n_leptos::provide_langid_context`).
 //!
 //! See the documentation for the `rtr!` macro for detailed usage examples.
+//!
+//! ## Native targets
+//!
+//! The crate also builds for targets other than `wasm32`, e.g. to share localization code
+//! with a desktop or CLI build. There, local storage is kept in memory, `change_langid`
+//! notifies the contexts of the same thread directly and the navigator language is never
+//! available. The browser-only parts, the `Intl` formatting helpers and
+//! `change_langid_debounced`, are only available on `wasm32`.
+//!
+//! ## Server functions
+//!
+//! `rtr!` needs a `LangIdContext` and creates signals, so it isn't meant for generating
+//! text on the server, e.g. for emails or notifications in a `#[server]` function. Use
+//! `translate_in` there instead, which takes the langid as a parameter and returns a plain
+//! `String`, with the same lookup and fallback behavior as `rtr!`. It doesn't need any
+//! Leptos context, falling back to the message ID if no `FallbackPolicy` is in scope.
+//!
+//! ## Custom Fluent functions
+//!
+//! This crate doesn't construct any Fluent bundles: `rtr!` queries the `i18n::Locales`
+//! static it is given (`LOCALES` by default) as is. Custom functions (e.g. a `PRICE()`
+//! formatter) therefore have to be registered on the bundles when the `Locales` are built
+//! with the `i18n` crate, after which they are available to every `rtr!` call.
 
 pub use i18n;
 pub use i18n_leptos_macros::*;
 
+mod attribute;
+mod cache;
 mod ctx;
+mod direction;
+mod error;
+mod fallback;
+#[cfg(target_arch = "wasm32")]
+mod fetch;
+#[cfg(target_arch = "wasm32")]
+mod format;
+#[cfg(target_arch = "wasm32")]
+mod head;
+mod inspect;
+mod locales;
+mod metrics;
+#[cfg(feature = "dev-warnings")]
+mod missing;
+mod pseudo;
+#[cfg(feature = "router")]
+mod router;
 mod utils;
 
+#[doc(hidden)]
+pub mod __private;
+
+pub use cache::*;
 pub use ctx::*;
+pub use direction::*;
+pub use error::*;
+pub use fallback::*;
+#[cfg(target_arch = "wasm32")]
+pub use fetch::*;
+#[cfg(target_arch = "wasm32")]
+pub use format::*;
+#[cfg(target_arch = "wasm32")]
+pub use head::*;
+pub use locales::*;
+pub use metrics::*;
+#[cfg(feature = "dev-warnings")]
+pub use missing::*;
+pub use pseudo::*;
+#[cfg(feature = "router")]
+pub use router::*;
 
 use leptos::prelude::*;
 
@@ -42,13 +104,98 @@ compile_error!("not implemented");
 /// when the language context changes.
 #[derive(Clone, Copy)]
 pub struct ReactiveMessage {
-    msg: RwSignal<i18n::Message>,
+    msg: Signal<i18n::Message>,
+    status: Signal<MessageStatus>,
+}
+
+/// The status of the translation held by a `ReactiveMessage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageStatus {
+    /// The message was resolved.
+    Resolved,
+    /// The message of the previous language is kept while the active language is loading.
+    Loading,
+    /// The lookup failed and the message holds a fallback value.
+    Fallback,
 }
 
 impl ReactiveMessage {
-    /// A new reactive message.
-    pub fn new(msg: RwSignal<i18n::Message>) -> Self {
-        Self { msg }
+    /// A new reactive message, backed by any signal of a message (e.g. a `RwSignal` or a `Memo`).
+    pub fn new(msg: impl Into<Signal<i18n::Message>>) -> Self {
+        Self {
+            msg: msg.into(),
+            status: Signal::stored(MessageStatus::Resolved),
+        }
+    }
+
+    /// Returns the signal of the message backing this `ReactiveMessage`, e.g. to read the
+    /// whole message in a custom effect.
+    ///
+    /// Messages created by `rtr!` are derived from the langid, so the signal is read-only.
+    /// To drive a message directly, create it with `ReactiveMessage::new` from a `RwSignal`
+    /// and keep the `RwSignal`: writing to it bypasses the langid-driven resolution, so
+    /// keeping the message in the active language is then the caller's responsibility.
+    pub fn as_signal(&self) -> Signal<i18n::Message> {
+        self.msg
+    }
+
+    /// Sets the signal of the status of the message's translation.
+    pub(crate) fn with_status(mut self, status: impl Into<Signal<MessageStatus>>) -> Self {
+        self.status = status.into();
+        self
+    }
+
+    /// Returns whether the message still shows the translation of the previous language,
+    /// as none of the messages of the active language could be resolved yet, e.g. while
+    /// its locales are being loaded. This can be used to show a spinner.
+    ///
+    /// Messages created by `rtr!`, `rtr_all`, `rtr_in`, `with_args` and `LocalizedDisplay`
+    /// types keep their last translation in that case instead of falling back to the message
+    /// ID. Messages created with `ReactiveMessage::new` are never loading.
+    ///
+    /// This is a reactive read.
+    pub fn loading(&self) -> bool {
+        self.status.get() == MessageStatus::Loading
+    }
+
+    /// Returns whether the lookup of the message failed, so that it holds a fallback value,
+    /// as given by the `FallbackPolicy` or the `default` of `rtr!`.
+    ///
+    /// Only messages created by `rtr!` track this, other messages are never a fallback.
+    ///
+    /// This is a reactive read.
+    pub fn is_fallback(&self) -> bool {
+        self.status.get() == MessageStatus::Fallback
+    }
+
+    /// Returns the translated value of the message, or `fallback` if its lookup failed,
+    /// e.g. for strings that aren't translated yet, see `is_fallback`.
+    ///
+    /// This is a reactive read.
+    pub fn value_or(&self, fallback: &str) -> String {
+        if self.is_fallback() {
+            fallback.to_string()
+        } else {
+            self.value()
+        }
+    }
+
+    /// Returns the translated value on a single line, e.g. for a button label showing a
+    /// multiline FTL value: runs of whitespace, including newlines, are collapsed into a
+    /// single space and leading and trailing whitespace is trimmed.
+    ///
+    /// This is a reactive read.
+    pub fn value_inline(&self) -> String {
+        self.msg
+            .with(|msg| msg.value.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Returns the translated value without bidi isolation marks, e.g. for the `value`
+    /// of an `<input>`, where the control characters would be part of the text.
+    ///
+    /// This is a reactive read.
+    pub fn value_stripped(&self) -> String {
+        strip_bidi_isolation(&self.value())
     }
 
     /// Returns the ID of the localized message.
@@ -70,7 +217,7 @@ impl ReactiveMessage {
         if !self.msg.is_disposed() {
             self.msg.read().value.clone()
         } else {
-            log::error!("i18n_leptos | reactive message signal disposed {:#?}", self.msg);
+            error::report_error(I18nError::Disposed(format!("{:#?}", self.msg)));
             Default::default()
         }
     }
@@ -80,46 +227,408 @@ impl ReactiveMessage {
         self.msg.read_untracked().value.clone()
     }
 
+    /// Returns a memo of the translated value transformed by `f`, which is re-run
+    /// whenever the value changes.
+    pub fn map<F>(&self, f: F) -> Memo<String>
+    where
+        F: Fn(String) -> String + Send + Sync + 'static,
+    {
+        let this = *self;
+        Memo::new(move |_| f(this.value()))
+    }
+
+    /// Converts the message into a signal of its translated value, e.g. for the
+    /// `Signal<String>` props of third-party components.
+    ///
+    /// Like `value`, the signal tracks both the langid and any signals read by the message's
+    /// arguments, so it updates whenever either changes.
+    pub fn into_signal(self) -> Signal<String> {
+        Signal::derive(move || self.value())
+    }
+
+    /// Returns a memo of the translated value with every occurrence of `placeholder` replaced
+    /// by the translated value of `other`, e.g. to embed a localized entity name in a toast.
+    /// It is re-run whenever either value changes.
+    pub fn combine(&self, placeholder: &str, other: ReactiveMessage) -> Memo<String> {
+        let placeholder = placeholder.to_string();
+        self.map(move |value| value.replace(&placeholder, &other.value()))
+    }
+
+    /// Returns a message with the same ID, resolved from `locales` with the arguments returned
+    /// by `args`, e.g. a live count.
+    ///
+    /// `args` is reactive: the message is re-resolved whenever the language or any signal read
+    /// by `args` changes, instead of having to recreate it with `rtr!` for every new value.
+    pub fn with_args<F>(&self, locales: &'static i18n::Locales, args: F) -> ReactiveMessage
+    where
+        F: Fn() -> i18n::FluentArgs<'static> + Send + Sync + 'static,
+    {
+        let id = self.id_untracked();
+        __private::reactive_query_with(id.clone(), None, None, move |langid| {
+            let query = __private::with_args(i18n::Query::new(id.as_str()), &args());
+            locales::query(locales, langid, &id, &query)
+        })
+    }
+
+    /// Returns a message that, if the lookup of this one fails, is resolved from `locales`
+    /// in the first language of `chain` that has it, before falling back, e.g. for legal
+    /// text that must be shown in a specific language rather than the message ID.
+    ///
+    /// The message is re-resolved whenever this one changes. Messages resolved from the chain
+    /// are looked up without the arguments of this one.
+    pub fn with_fallback_chain(
+        &self,
+        locales: &'static i18n::Locales,
+        chain: Vec<i18n::LanguageIdentifier>,
+    ) -> ReactiveMessage {
+        let this = *self;
+        let (msg, status) = __private::split_status(ArcMemo::new_with_compare(
+            move |_| {
+                let status = this.status.get();
+                let msg = this.msg.get();
+                if status != MessageStatus::Fallback {
+                    return (msg, status);
+                }
+                locales::track_added_locales();
+                let id = msg.id.clone();
+                let query = i18n::Query::new(id.as_str());
+                chain
+                    .iter()
+                    .find_map(|langid| locales::query(locales, langid, &id, &query).ok())
+                    .map(|msg| (__private::postprocess(msg), MessageStatus::Resolved))
+                    .unwrap_or((msg, status))
+            },
+            |_, _| true,
+        ));
+
+        ReactiveMessage::new(msg).with_status(status)
+    }
+
     /// Returns the value of a specific attribute of the message.
     /// If the attribute is not found, it returns the attribute name itself.
     ///
     /// This is a reactive read.
     pub fn attr(&self, attr: &str, args: Option<&i18n::FluentArgs>) -> String {
+        self.attr_or(attr, args, attr)
+    }
+
+    /// Like `attr`, but returns `default` instead of the attribute name if the attribute
+    /// is not found or fails to localize.
+    ///
+    /// This is a reactive read.
+    pub fn attr_or(&self, attr: &str, args: Option<&i18n::FluentArgs>, default: &str) -> String {
         self.msg.track();
-        self.msg
-            .write_untracked()
-            .attrs
-            .get_mut(attr)
-            .map(|attr_cache| match attr_cache.query(args, true) {
-                Ok(value) => value,
-                Err(err) => {
-                    log::error!(
-                        "i18n_leptos | an error occurred during localization of '{attr}': {err:?}"
-                    );
-                    attr.to_string()
-                }
-            })
-            .unwrap_or_else(move || attr.to_string())
+        self.attr_or_untracked(attr, args, default)
     }
 
     /// Returns the value of a specific attribute of the message without tracking.
     /// If the attribute is not found, it returns the attribute name itself.
     pub fn attr_untracked(&self, attr: &str, args: Option<&i18n::FluentArgs>) -> String {
+        self.attr_or_untracked(attr, args, attr)
+    }
+
+    /// Like `attr_or`, but without tracking.
+    pub fn attr_or_untracked(
+        &self,
+        attr: &str,
+        args: Option<&i18n::FluentArgs>,
+        default: &str,
+    ) -> String {
+        // query a copy of the attribute, so that reading attributes only ever takes a read lock
+        // on the signal and can be nested within other reads of the same message
         self.msg
-            .write_untracked()
-            .attrs
-            .get_mut(attr)
-            .map(|attr_cache| match attr_cache.query(args, true) {
-                Ok(value) => value,
-                Err(err) => {
-                    log::error!(
-                        "i18n_leptos | an error occurred during localization of '{attr}': {err:?}"
-                    );
-                    attr.to_string()
-                }
+            .with_untracked(|msg| msg.attrs.get(attr).cloned())
+            .map(|mut attr_cache| {
+                attr_cache
+                    .query(args, true)
+                    .unwrap_or_else(|err| attr_error_fallback(attr, err, default))
             })
-            .unwrap_or_else(move || attr.to_string())
+            .unwrap_or_else(move || default.to_string())
+    }
+
+    /// Returns a signal of the value of the attribute `attr`, e.g. for binding it directly to
+    /// a DOM attribute: `placeholder=msg.attr_signal("placeholder")`.
+    ///
+    /// Like `attr`, it tracks the message, so it updates when the language changes,
+    /// and falls back to the attribute name if the attribute is not found.
+    pub fn attr_signal(&self, attr: &str) -> Signal<String> {
+        let this = *self;
+        let attr = attr.to_string();
+        Signal::derive(move || this.attr(&attr, None))
+    }
+
+    /// Like `attr_signal`, but passes the arguments returned by `args` to the attribute,
+    /// which is re-run whenever the message or any signal read by `args` changes.
+    pub fn attr_signal_with_args<F>(&self, attr: &str, args: F) -> Signal<String>
+    where
+        F: Fn() -> i18n::FluentArgs<'static> + Send + Sync + 'static,
+    {
+        let this = *self;
+        let attr = attr.to_string();
+        Signal::derive(move || this.attr(&attr, Some(&args())))
+    }
+
+    /// Returns whether the message has the attribute `attr`, e.g. to only render a tooltip
+    /// if there is one, as `attr` can't tell a missing attribute from one translated
+    /// to its own name.
+    ///
+    /// This is a reactive read.
+    pub fn has_attr(&self, attr: &str) -> bool {
+        self.msg.with(|msg| msg.attrs.contains_key(attr))
     }
+
+    /// Returns the name and value of every attribute of the message, sorted by name.
+    /// Attributes that fail to localize resolve to their own name, like in `attr`.
+    ///
+    /// This is a reactive read.
+    pub fn attrs(&self) -> Vec<(String, String)> {
+        self.msg.track();
+        self.attrs_untracked()
+    }
+
+    /// Returns the name and value of every attribute of the message without tracking.
+    pub fn attrs_untracked(&self) -> Vec<(String, String)> {
+        let mut attrs: Vec<_> = self
+            .msg
+            .with_untracked(|msg| msg.attrs.clone())
+            .into_iter()
+            .map(|(name, mut attr_cache)| {
+                let value = attr_cache
+                    .query(None, true)
+                    .unwrap_or_else(|err| attr_error_fallback(&name, err, &name));
+                (name.to_string(), value)
+            })
+            .collect();
+        attrs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        attrs
+    }
+}
+
+/// Reports a failed localization of the attribute `attr` and returns the attribute name itself.
+fn attr_error_fallback(attr: &str, err: impl std::fmt::Debug, default: &str) -> String {
+    error::report_error(I18nError::Attribute {
+        attr: attr.to_string(),
+        error: format!("{err:?}"),
+    });
+    default.to_string()
+}
+
+/// Translates every message ID in `ids` using `locales`, like calling `rtr!` for each of them.
+///
+/// All messages are re-resolved by a single effect when the language changes, instead of
+/// one effect per message, which adds up for large fixed sets like a sidebar or an enum.
+/// Each returned message is still individually reactive, and reports whether it fell back
+/// or is loading like the messages of `rtr!`.
+pub fn rtr_all(locales: &'static i18n::Locales, ids: &[&str]) -> Vec<ReactiveMessage> {
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let resolve = move |prev: Option<&__private::StatusMessage>,
+                        id: &str,
+                        langid: &i18n::LanguageIdentifier| {
+        let result = cache::cached_query(locales, id, langid);
+        __private::retain_while_loading(prev, id, langid, result, None)
+    };
+
+    // resolve the messages eagerly, so that they are correct before the effect first runs
+    let langid = ctx::expect_langid();
+    let states: Vec<RwSignal<__private::StatusMessage>> = langid.with_untracked(|langid| {
+        ids.iter()
+            .map(|id| RwSignal::new(untrack(|| resolve(None, id, langid))))
+            .collect()
+    });
+
+    Effect::new({
+        let states = states.clone();
+        move |prev: Option<()>| {
+            locales::track_added_locales();
+            let langid = langid.get();
+            // the first run only subscribes to the langid
+            if prev.is_some() {
+                for (id, state) in ids.iter().zip(&states) {
+                    let next = state.with_untracked(|prev| resolve(Some(prev), id, &langid));
+                    state.set(next);
+                }
+            }
+        }
+    });
+
+    states
+        .into_iter()
+        .map(|state| {
+            let msg = Signal::derive(move || state.with(|(msg, _)| msg.clone()));
+            let status = Signal::derive(move || state.with(|(_, status)| *status));
+            ReactiveMessage::new(msg).with_status(status)
+        })
+        .collect()
+}
+
+/// Translates the message `id` in `langid` regardless of the language of the context,
+/// e.g. for labeling each option of a language switcher in its own language.
+///
+/// This isn't reactive, see `rtr_in` for a reactive variant. It doesn't need a Leptos
+/// context or signals either, so it can be used to localize text in `#[server]` functions.
+/// Lookups that fail fall back according to the `FallbackPolicy` in scope, if any.
+pub fn translate_in(
+    locales: &'static i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    args: Option<&i18n::FluentArgs<'static>>,
+) -> String {
+    let mut query = i18n::Query::new(id);
+    if let Some(args) = args {
+        query = __private::with_args(query, args);
+    }
+    locales::query(locales, langid, id, &query)
+        .map(__private::postprocess)
+        .unwrap_or_else(|errs| __private::fallback_message(id, langid, errs))
+        .value
+}
+
+/// Translates every message of `ids` in `langid` at once into plain strings, keyed by ID,
+/// e.g. for capturing the text of a statically generated page or an email template at
+/// build time.
+///
+/// Like `translate_in`, this is the non-reactive counterpart of `rtr!`: the messages are
+/// resolved eagerly without creating any signal, so it needs neither a Leptos owner nor
+/// a `LangIdContext`. Lookups that fail fall back according to the `FallbackPolicy` in
+/// scope, if any. For messages with arguments, call `translate_in` for each of them.
+pub fn translate_all_in(
+    locales: &'static i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    ids: &[&str],
+) -> std::collections::HashMap<String, String> {
+    ids.iter()
+        .map(|id| (id.to_string(), translate_in(locales, langid, id, None)))
+        .collect()
+}
+
+/// Resolves the message `id` in `langid` from `locales` alone, returning every error
+/// of a failed resolution, e.g. for a translation QA mode reporting why messages fail
+/// (a missing reference, a cyclic reference, an argument of the wrong type, ...).
+///
+/// Cyclic references between messages are returned as a `MessageError::CyclicReference`
+/// naming the references along the cycle, and every other error as reported by Fluent.
+/// Strings passed for an argument the message selects a plural category or number on,
+/// e.g. `count = "5"` for `{ $count -> [one] ... *[other] ... }`, are returned as a
+/// `MessageError::ArgumentType`, even though Fluent resolves them to the default variant.
+///
+/// Unlike `rtr!` and `translate_in`, this doesn't fall back to the locales added with
+/// `add_locales` or to the `FallbackPolicy`, and doesn't report errors to the error handler.
+pub fn query_message(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    args: Option<&i18n::FluentArgs<'static>>,
+) -> Result<i18n::Message, Vec<MessageError>> {
+    let mut query = i18n::Query::new(id);
+    if let Some(args) = args {
+        query = __private::with_args(query, args);
+    }
+    let mut arg_errors = args
+        .map(|args| inspect::argument_errors(locales, langid, id, args))
+        .unwrap_or_default();
+    match locales.query(langid, &query) {
+        Ok(msg) if arg_errors.is_empty() => Ok(msg),
+        Ok(_) => Err(arg_errors),
+        Err(errs) => {
+            let mut errors = inspect::message_errors(locales, langid, id, errs);
+            errors.append(&mut arg_errors);
+            Err(errors)
+        }
+    }
+}
+
+/// Translates the message `id` in the language of the context at the time of the call,
+/// e.g. for a toast or an error message captured into a closure.
+///
+/// This is a plain untracked read that doesn't create any signal, so unlike a throwaway
+/// `rtr!` call, it costs nothing once returned, but it also never updates.
+/// Panics if no `LangIdContext` is provided.
+#[track_caller]
+pub fn translate_now(
+    locales: &'static i18n::Locales,
+    id: &str,
+    args: Option<&i18n::FluentArgs<'static>>,
+) -> String {
+    let langid = ctx::expect_langid().get_untracked();
+    translate_in(locales, &langid, id, args)
+}
+
+/// Translates the message `id` in `langid` regardless of the language of the context,
+/// like `translate_in`, but as a `ReactiveMessage` that is re-resolved when `langid` changes.
+pub fn rtr_in(
+    locales: &'static i18n::Locales,
+    langid: impl Into<Signal<i18n::LanguageIdentifier>>,
+    id: &str,
+) -> ReactiveMessage {
+    let id = id.to_string();
+    __private::reactive_query_with(id.clone(), None, Some(langid.into()), move |langid| {
+        locales::query(locales, langid, &id, &i18n::Query::new(id.as_str()))
+    })
+}
+
+/// Returns the IDs of every message of the bundle of `langid`, e.g. for a debug overlay
+/// listing every translatable string of the current language.
+///
+/// The IDs are read from the FTL resources of `locales` and of the locales added with
+/// `add_locales`, sorted and without duplicates. Terms aren't included, as they can't be
+/// looked up by themselves.
+pub fn message_ids(
+    locales: &'static i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+) -> Vec<String> {
+    locales::all_message_ids(locales, langid)
+}
+
+/// Two reactive messages are equal if their messages currently have the same ID.
+///
+/// The comparison is an untracked snapshot read, so it isn't reactive and its result
+/// can change once the language changes.
+impl PartialEq for ReactiveMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.msg
+            .with_untracked(|msg| other.msg.with_untracked(|other| msg.id == other.id))
+    }
+}
+
+/// A type whose values select a variant of a Fluent select expression, e.g. an enum of
+/// genders matching `{ $gender -> [male] ... [female] ... *[other] ... }`.
+///
+/// Passing such a value to `rtr!` as `select_gender = value` keeps the Rust values and
+/// the FTL variant keys in one place, instead of passing stringly-typed keys around.
+/// It can be derived for enums of unit variants, see `derive(FluentSelector)`.
+pub trait FluentSelector {
+    /// Returns the variant key selected by this value.
+    fn selector(&self) -> &'static str;
+}
+
+/// A set of arguments passed to a message with `rtr!("id", args = value)`.
+///
+/// Besides `FluentArgs` built at runtime, it is implemented by the argument structs
+/// generated by `ftl_keys!`, which have a field for every variable of their message,
+/// so that forgetting or misspelling an argument fails to compile.
+pub trait MessageArgs {
+    /// Adds the arguments to `query`.
+    fn add_to_query(&self, query: i18n::Query) -> i18n::Query;
+}
+
+impl MessageArgs for i18n::FluentArgs<'static> {
+    fn add_to_query(&self, mut query: i18n::Query) -> i18n::Query {
+        for (key, value) in self.iter() {
+            query = query.with_arg(key.to_string(), __private::arg(value.clone()));
+        }
+        query
+    }
+}
+
+/// A type that is localized with arguments given where it is displayed, e.g. a unit system,
+/// which `rtr!` passes through as `rtr!(obj, "unit" = unit_system)`.
+pub trait LocalizedDisplayWithArgs {
+    /// Localizes the implementor in `langid` with `args`.
+    fn localize_with_args(
+        &self,
+        langid: &i18n::LanguageIdentifier,
+        args: &i18n::FluentArgs,
+    ) -> i18n::Message;
 }
 
 /// A trait for types that can be reactively localized.
@@ -132,14 +641,21 @@ impl<T> ReactiveLocalizedDisplay for T
 where
     T: i18n::LocalizedDisplay + Send + Sync + 'static,
 {
+    #[track_caller]
     fn reactive_localize(self) -> ReactiveMessage {
-        let msg = RwSignal::default();
-
-        Effect::new(move || {
-            let langid = ctx::expect_langid();
-            msg.set(self.localize(&langid.get()));
-        });
+        // derive the message from the langid instead of writing it from an effect, so that
+        // it is correct from the first read rather than only after the effect has run
+        let langid = ctx::expect_langid();
+        let (msg, status) = __private::split_status(ArcMemo::new_with_compare(
+            move |prev| {
+                locales::track_added_locales();
+                let langid = langid.get();
+                let msg = self.localize(&langid);
+                __private::retain_localized_while_loading(prev, &langid, msg)
+            },
+            |_, _| true,
+        ));
 
-        ReactiveMessage { msg }
+        ReactiveMessage::new(msg).with_status(status)
     }
 }
@@ -0,0 +1,243 @@
+use crate::{I18nError, MessageError};
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::rc::Rc;
+use thiserror::Error;
+
+/// The number of failed lookups for a langid without a single successful one after which
+/// its locales are reported as not loaded.
+const NOT_LOADED_THRESHOLD: usize = 20;
+
+thread_local! {
+    static ADDED_LOCALES: ArcRwSignal<Vec<&'static i18n::Locales>> = ArcRwSignal::new(Vec::new());
+    /// The langids marked as loaded, which lookups add to without notifying anything,
+    /// as they run within the memos of messages, which mustn't write signals.
+    static LOADED_LOCALES: RefCell<HashSet<i18n::LanguageIdentifier>> = Default::default();
+    /// Notified when `load_best_locale` has loaded a locale.
+    static LOCALE_LOADED: ArcTrigger = ArcTrigger::new();
+    static FAILED_LOOKUPS: RefCell<HashMap<i18n::LanguageIdentifier, usize>> = Default::default();
+}
+
+/// Returns whether the locales of `langid` are loaded, i.e. whether any message has been
+/// resolved for it so far or `load_best_locale` has loaded it.
+///
+/// This is a reactive read, which is notified when `load_best_locale` has loaded a locale
+/// or locales are added with `add_locales`, so messages keeping the previous language while
+/// `langid` is loading are re-resolved once it is loaded.
+///
+/// This also helps diagnosing setups where all text shows raw message IDs, e.g. because the
+/// `Locales` have no bundle for `langid`, as opposed to a single missing message. Such
+/// setups are also reported once to the error handler as `I18nError::LocaleNotLoaded`,
+/// after a number of lookups for `langid` have failed without any succeeding.
+pub fn is_locale_loaded(langid: &i18n::LanguageIdentifier) -> bool {
+    LOCALE_LOADED.with(|loaded| loaded.track());
+    track_added_locales();
+    LOADED_LOCALES.with_borrow(|loaded| loaded.contains(langid))
+}
+
+/// Marks the locales of `langid` as loaded, without notifying `is_locale_loaded` readers.
+fn mark_locale_loaded(langid: &i18n::LanguageIdentifier) {
+    LOADED_LOCALES.with_borrow_mut(|loaded| loaded.insert(langid.clone()));
+}
+
+/// Records the outcome of a lookup for `langid`, reporting it as not loaded once
+/// too many lookups have failed without any succeeding.
+fn record_lookup(langid: &i18n::LanguageIdentifier, resolved: bool) {
+    if resolved {
+        mark_locale_loaded(langid);
+        return;
+    }
+    if LOADED_LOCALES.with_borrow(|loaded| loaded.contains(langid)) {
+        return;
+    }
+    let failed = FAILED_LOOKUPS.with_borrow_mut(|failed| {
+        let failed = failed.entry(langid.clone()).or_default();
+        *failed += 1;
+        *failed
+    });
+    if failed == NOT_LOADED_THRESHOLD {
+        crate::error::report_error(I18nError::LocaleNotLoaded(langid.clone()));
+    }
+}
+
+/// Makes the messages of `locales` available to every lookup, e.g. the translations
+/// shipped by a dynamically loaded feature module.
+///
+/// Lookups that fail in the `Locales` passed to `rtr!` fall through to the added ones,
+/// in the order they were added. Messages that are already rendered are re-resolved, so
+/// messages that were missing pick up the newly added translations.
+///
+/// If several of them define the same message ID, the `Locales` passed to `rtr!` win,
+/// followed by the earliest added one, so adding translations never changes a message
+/// that could already be resolved. Locales built at runtime can be made `'static` with
+/// `Box::leak`, as they are never removed.
+///
+/// Like `bump_locale_version`, this removes the resolved messages from the cache of messages
+/// without arguments, so that they are queried again with the added locales.
+pub fn add_locales(locales: &'static i18n::Locales) {
+    crate::cache::clear_resolved_messages();
+    ADDED_LOCALES.with(|added| added.write().push(locales));
+}
+
+/// Re-resolves every message that is rendered, e.g. after an in-browser FTL editor has
+/// edited the bundles of the `Locales` in place, so that edits show up without reloading.
+///
+/// Resolved messages are removed from the cache of messages without arguments first,
+/// so those are queried again too. This is meant for development tools: bumping the
+/// version re-runs every `rtr!` lookup at once.
+pub fn bump_locale_version() {
+    crate::cache::clear_resolved_messages();
+    ADDED_LOCALES.with(|added| added.update(|_| {}));
+}
+
+/// Subscribes the current reactive computation to `add_locales` and `bump_locale_version`
+/// calls.
+pub(crate) fn track_added_locales() {
+    ADDED_LOCALES.with(|added| added.track());
+}
+
+/// Queries `locales` for the message `id`, falling back to the locales added with `add_locales`.
+/// Returns the errors of `locales` if none of them can resolve the query.
+pub(crate) fn query(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    query: &i18n::Query,
+) -> Result<i18n::Message, Vec<MessageError>> {
+    crate::metrics::record_resolution(|| query_all(locales, langid, id, query))
+}
+
+fn query_all(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+    id: &str,
+    query: &i18n::Query,
+) -> Result<i18n::Message, Vec<MessageError>> {
+    let errors = match locales.query(langid, query) {
+        Ok(msg) => {
+            record_lookup(langid, true);
+            return Ok(msg);
+        }
+        Err(errs) => crate::inspect::message_errors(locales, langid, id, errs),
+    };
+    let msg = ADDED_LOCALES.with(|added| {
+        added.with_untracked(|added| {
+            added
+                .iter()
+                .find_map(|locales| locales.query(langid, query).ok())
+        })
+    });
+    record_lookup(langid, msg.is_some());
+    msg.ok_or(errors)
+}
+
+/// Returns the FTL resources of `locales` for `langid`, for inspecting their messages.
+///
+/// `i18n::Message` only carries resolved strings, so everything that needs the messages
+/// themselves (e.g. the IDs of a bundle) reads them from here.
+pub(crate) fn resources<'a>(
+    locales: &'a i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+) -> Vec<&'a fluent_bundle::FluentResource> {
+    locales.resources(langid).into_iter().flatten().collect()
+}
+
+/// Returns the IDs of the messages of `locales` and of the added locales for `langid`,
+/// sorted and without duplicates.
+pub(crate) fn all_message_ids(
+    locales: &i18n::Locales,
+    langid: &i18n::LanguageIdentifier,
+) -> Vec<String> {
+    let mut ids = std::collections::BTreeSet::new();
+    let mut add_ids = |locales: &i18n::Locales| {
+        for resource in resources(locales, langid) {
+            for entry in resource.entries() {
+                if let fluent_syntax::ast::Entry::Message(msg) = entry {
+                    ids.insert(msg.id.name.to_string());
+                }
+            }
+        }
+    };
+    add_ids(locales);
+    ADDED_LOCALES.with(|added| {
+        added.with_untracked(|added| added.iter().for_each(|locales| add_ids(locales)))
+    });
+    ids.into_iter().collect()
+}
+
+/// An error returned by the resource of `load_best_locale`.
+#[derive(Error, Debug, Clone)]
+pub enum LoadLocaleError<E> {
+    #[error("no locales are available")]
+    NoLocales,
+    #[error("failed to fetch the locales of '{langid}': {error}")]
+    Fetch {
+        langid: i18n::LanguageIdentifier,
+        error: E,
+    },
+}
+
+/// Loads the translations of the available locale best matching the active language,
+/// for apps that fetch their locales lazily instead of shipping all of them.
+///
+/// Whenever the langid changes, it is negotiated against `available` (falling back to the
+/// first available locale if none match), the `Locales` of the match are fetched with
+/// `fetcher` unless they already were, and added with `add_locales`. The langid is then
+/// set to the match, so messages switch to the new language at once when it is loaded.
+/// Until then, they keep showing the previous language, see `ReactiveMessage::loading`.
+///
+/// The returned resource holds the loaded langid or the error, so it can be awaited in a
+/// `Suspense` or `Transition`. Panics if no `LangIdContext` is provided.
+///
+/// ```ignore
+/// let locale = load_best_locale(available_locales(), |langid| async move {
+///     let ftl = fetch_text(&format!("/locales/{langid}.ftl")).await?;
+///     Ok(&*Box::leak(Box::new(build_locales(&langid, ftl)?)))
+/// });
+/// ```
+pub fn load_best_locale<F, Fut, E>(
+    available: &[i18n::LanguageIdentifier],
+    fetcher: F,
+) -> LocalResource<Result<i18n::LanguageIdentifier, LoadLocaleError<E>>>
+where
+    F: Fn(i18n::LanguageIdentifier) -> Fut + 'static,
+    Fut: Future<Output = Result<&'static i18n::Locales, E>> + 'static,
+    E: 'static,
+{
+    let langid = crate::ctx::use_langid_rw_signal().expect("a LangIdContext should be provided");
+    let available: Rc<[i18n::LanguageIdentifier]> = available.into();
+    let fetcher = Rc::new(fetcher);
+    let loaded: Rc<RefCell<Vec<i18n::LanguageIdentifier>>> = Default::default();
+
+    LocalResource::new(move || {
+        let requested = langid.get();
+        let best = crate::negotiate(std::slice::from_ref(&requested), &available)
+            .or_else(|| available.first().cloned());
+        let langid = langid.clone();
+        let fetcher = fetcher.clone();
+        let loaded = loaded.clone();
+        async move {
+            let best = best.ok_or(LoadLocaleError::NoLocales)?;
+            if !loaded.borrow().contains(&best) {
+                let locales =
+                    fetcher(best.clone())
+                        .await
+                        .map_err(|error| LoadLocaleError::Fetch {
+                            langid: best.clone(),
+                            error,
+                        })?;
+                add_locales(locales);
+                mark_locale_loaded(&best);
+                LOCALE_LOADED.with(|loaded| loaded.notify());
+                loaded.borrow_mut().push(best.clone());
+            }
+            // setting the same langid again would re-run the resource forever
+            if best != requested {
+                langid.set(best.clone());
+            }
+            Ok(best)
+        }
+    })
+}
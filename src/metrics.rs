@@ -0,0 +1,108 @@
+#[cfg(feature = "metrics")]
+use std::cell::Cell;
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+/// Counters of the message resolutions done so far, see `translation_stats`.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranslationStats {
+    /// The number of message lookups, including failed ones.
+    pub resolutions: u64,
+    /// The number of message lookups that failed and fell back.
+    pub failures: u64,
+    /// The total time spent resolving messages.
+    pub total_time: Duration,
+}
+
+#[cfg(feature = "metrics")]
+thread_local! {
+    static STATS: Cell<TranslationStats> = Cell::default();
+}
+
+/// Returns the counters of the message resolutions done on this thread since the last
+/// `reset_translation_stats` call, e.g. to find out how many messages a language switch
+/// re-resolves and how long that takes:
+///
+/// ```ignore
+/// reset_translation_stats();
+/// change_langid_and_settle(langid).await;
+/// log::info!("{:?}", translation_stats());
+/// ```
+///
+/// Only available with the `metrics` feature. Messages served from the message cache
+/// aren't resolutions, so they aren't counted.
+#[cfg(feature = "metrics")]
+pub fn translation_stats() -> TranslationStats {
+    STATS.get()
+}
+
+/// Resets the counters returned by `translation_stats`.
+///
+/// Only available with the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub fn reset_translation_stats() {
+    STATS.set(TranslationStats::default());
+}
+
+/// Measures the time since it was started, with `performance.now()` in the browser,
+/// where `Instant` panics.
+#[cfg(all(feature = "metrics", target_arch = "wasm32"))]
+struct Timer(Option<f64>);
+
+#[cfg(all(feature = "metrics", target_arch = "wasm32"))]
+impl Timer {
+    fn now() -> Option<f64> {
+        leptos::prelude::window()
+            .performance()
+            .map(|performance| performance.now())
+    }
+
+    fn start() -> Self {
+        Self(Self::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        match (self.0, Self::now()) {
+            (Some(start), Some(now)) => Duration::from_secs_f64((now - start).max(0.0) / 1000.0),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// Measures the time since it was started.
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+struct Timer(std::time::Instant);
+
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+impl Timer {
+    fn start() -> Self {
+        Self(std::time::Instant::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Runs `resolve` and records it in the `translation_stats`.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_resolution<T, E>(resolve: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let timer = Timer::start();
+    let result = resolve();
+    let elapsed = timer.elapsed();
+
+    let mut stats = STATS.get();
+    stats.resolutions += 1;
+    stats.failures += u64::from(result.is_err());
+    stats.total_time += elapsed;
+    STATS.set(stats);
+    result
+}
+
+/// Runs `resolve`, as there is nothing to record without the `metrics` feature.
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record_resolution<T, E>(resolve: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    resolve()
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// A message lookup that failed and fell back to the message ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MissingTranslation {
+    /// The ID of the message that could not be resolved.
+    pub id: String,
+    /// The language identifier that was active when the lookup failed.
+    pub langid: i18n::LanguageIdentifier,
+}
+
+thread_local! {
+    static MISSING_TRANSLATIONS: ArcRwSignal<Vec<MissingTranslation>> = ArcRwSignal::new(Vec::new());
+    /// The lookups recorded so far, including the ones not written to the signal yet.
+    static RECORDED: RefCell<HashSet<MissingTranslation>> = Default::default();
+}
+
+/// Returns a signal holding every message lookup that fell back to its ID so far,
+/// in the order they first occurred and without duplicates.
+///
+/// Lookups are added to the signal in a task spawned when they fail, rather than by the
+/// lookup itself, which runs within the memo of a message and mustn't write signals.
+///
+/// Only available with the `dev-warnings` feature.
+pub fn use_missing_translations() -> ArcReadSignal<Vec<MissingTranslation>> {
+    MISSING_TRANSLATIONS.with(|missing| missing.read_only())
+}
+
+/// Records a failed lookup of `id` for `langid`, unless it was already recorded.
+pub(crate) fn record_missing_translation(id: &str, langid: &i18n::LanguageIdentifier) {
+    let entry = MissingTranslation {
+        id: id.to_string(),
+        langid: langid.clone(),
+    };
+    if !RECORDED.with_borrow_mut(|recorded| recorded.insert(entry.clone())) {
+        return;
+    }
+    crate::error::report_error(crate::I18nError::MissingTranslation {
+        id: entry.id.clone(),
+        langid: entry.langid.clone(),
+    });
+    // writing the signal from the memo would re-run the memos reading it, including this one
+    leptos::task::spawn_local(async move {
+        MISSING_TRANSLATIONS.with(|missing| missing.write().push(entry));
+    });
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+/// The accented look-alikes of the ASCII uppercase letters, from `A` to `Z`.
+const ACCENTED_UPPERCASE: [char; 26] = [
+    'Å', 'Ɓ', 'Ç', 'Ð', 'É', 'Ƒ', 'Ĝ', 'Ĥ', 'Î', 'Ĵ', 'Ķ', 'Ļ', 'Ṁ', 'Ñ', 'Ö', 'Þ', 'Ǫ', 'Ŕ', 'Š',
+    'Ţ', 'Û', 'Ṽ', 'Ŵ', 'Ẋ', 'Ý', 'Ž',
+];
+
+/// The accented look-alikes of the ASCII lowercase letters, from `a` to `z`.
+const ACCENTED_LOWERCASE: [char; 26] = [
+    'å', 'ƀ', 'ç', 'ð', 'é', 'ƒ', 'ĝ', 'ĥ', 'î', 'ĵ', 'ķ', 'ļ', 'ɱ', 'ñ', 'ö', 'þ', 'ǫ', 'ŕ', 'š',
+    'ţ', 'û', 'ṽ', 'ŵ', 'ẋ', 'ý', 'ž',
+];
+
+/// Newtype wrapper around whether pseudo-localization is enabled.
+#[derive(Debug, Clone, Copy)]
+struct PseudoLocalization(bool);
+
+/// Enables or disables pseudo-localization for the `rtr!` calls in the current component
+/// and its children, e.g. in a QA build.
+///
+/// When enabled, every resolved message is transformed by `pseudo_localize`, so that
+/// text which isn't localized stands out by looking normal, and layouts too tight for
+/// longer translations overflow. Messages falling back to their ID aren't transformed.
+pub fn provide_pseudo_localization(enabled: bool) {
+    provide_context(PseudoLocalization(enabled));
+}
+
+/// Returns whether pseudo-localization is enabled in scope.
+pub(crate) fn is_pseudo_localized() -> bool {
+    use_context::<PseudoLocalization>().is_some_and(|pseudo| pseudo.0)
+}
+
+/// Pseudo-localizes `value`: ASCII letters are replaced by accented look-alikes, the text
+/// is padded by about 30% to simulate longer translations, and it is wrapped in brackets
+/// to show truncation, e.g. "Welcome" becomes "[Ŵéļçöɱé~~~]".
+pub fn pseudo_localize(value: &str) -> String {
+    let accented: String = value
+        .chars()
+        .map(|c| match c {
+            'A'..='Z' => ACCENTED_UPPERCASE[(c as u8 - b'A') as usize],
+            'a'..='z' => ACCENTED_LOWERCASE[(c as u8 - b'a') as usize],
+            c => c,
+        })
+        .collect();
+    let padding = (value.chars().count() * 3).div_ceil(10);
+    format!("[{accented}{}]", "~".repeat(padding))
+}
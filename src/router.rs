@@ -0,0 +1,125 @@
+//! Integration with `leptos_router` for locale-prefixed URLs like `/fr/about`.
+
+use crate::ctx;
+use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_navigate};
+use leptos_router::NavigateOptions;
+
+/// Returns the available locale matching the leading segment of `path`, if any,
+/// along with the rest of the path.
+fn split_locale_prefix<'a>(
+    path: &'a str,
+    available: &[i18n::LanguageIdentifier],
+) -> Option<(i18n::LanguageIdentifier, &'a str)> {
+    let path = path.trim_start_matches('/');
+    let (segment, rest) = path.split_at(path.find('/').unwrap_or(path.len()));
+    available
+        .iter()
+        .find(|langid| langid.to_string().eq_ignore_ascii_case(segment))
+        .map(|langid| (langid.clone(), rest))
+}
+
+fn localize_path_in(
+    path: &str,
+    langid: &i18n::LanguageIdentifier,
+    available: &[i18n::LanguageIdentifier],
+) -> String {
+    let rest = match split_locale_prefix(path, available) {
+        Some((_, rest)) => rest,
+        None if path == "/" => "",
+        None => path,
+    };
+    format!("/{langid}{rest}")
+}
+
+/// Appends the query and the hash of a location to `href`.
+fn push_search_and_hash(href: &mut String, search: &str, hash: &str) {
+    if !search.is_empty() {
+        href.push('?');
+        href.push_str(search);
+    }
+    if !hash.is_empty() {
+        if !hash.starts_with('#') {
+            href.push('#');
+        }
+        href.push_str(hash);
+    }
+}
+
+/// Returns `path` with its locale prefix replaced by `langid`, or prefixed with `langid`
+/// if it has none, e.g. `/fr/about` becomes `/en-US/about` for `en-US`.
+///
+/// Only the available locales are recognized as prefixes, see `provide_available_locales`.
+pub fn localize_path(path: &str, langid: &i18n::LanguageIdentifier) -> String {
+    let available = ctx::use_available_locales().unwrap_or_default();
+    localize_path_in(path, langid, &available)
+}
+
+/// Returns the current URL with its locale prefix replaced by `langid`, keeping the rest
+/// of the path, the query and the hash, e.g. for the links of a language switcher.
+/// Must be called within a `Router`.
+pub fn use_localized_href(langid: i18n::LanguageIdentifier) -> Signal<String> {
+    let available = ctx::use_available_locales().unwrap_or_default();
+    let location = use_location();
+    Signal::derive(move || {
+        let mut href = localize_path_in(&location.pathname.get(), &langid, &available);
+        push_search_and_hash(&mut href, &location.search.get(), &location.hash.get());
+        href
+    })
+}
+
+/// Keeps the langid of the `LangIdContext` and the locale prefix of the URL in sync,
+/// for SEO-friendly URLs like `/fr/about`. Must be called within a `Router`, after the
+/// available locales and the `LangIdContext` have been provided. Panics otherwise.
+///
+/// Navigating to a prefixed path switches the language to the prefix's locale, and
+/// changing the language replaces the prefix of the current URL. A path without a
+/// prefix is redirected to the same path prefixed with the current langid.
+/// Only the available locales are recognized as prefixes, as otherwise any leading
+/// segment like `/about` would be ambiguous.
+pub fn sync_langid_with_route() {
+    let langid = ctx::use_langid_rw_signal().expect("a LangIdContext should be provided");
+    let available = ctx::use_available_locales().expect("available locales should be provided");
+    let location = use_location();
+    let navigate = use_navigate();
+
+    // switch to the URL's language right away, so that the effect below doesn't
+    // redirect to the initial langid before the URL has been read
+    let set_langid_from_path = {
+        let langid = langid.clone();
+        let available = available.clone();
+        move |path: &str| {
+            if let Some((path_langid, _)) = split_locale_prefix(path, &available) {
+                if langid.get_untracked() != path_langid {
+                    langid.set(path_langid);
+                }
+            }
+        }
+    };
+    set_langid_from_path(&location.pathname.get_untracked());
+
+    // URL -> langid
+    Effect::new(move |_| set_langid_from_path(&location.pathname.get()));
+
+    // langid -> URL
+    Effect::new(move |_| {
+        let langid = langid.get();
+        let path = location.pathname.get_untracked();
+        let path_langid = split_locale_prefix(&path, &available).map(|(langid, _)| langid);
+        if path_langid.as_ref() != Some(&langid) {
+            let mut href = localize_path_in(&path, &langid, &available);
+            push_search_and_hash(
+                &mut href,
+                &location.search.get_untracked(),
+                &location.hash.get_untracked(),
+            );
+            navigate(
+                &href,
+                NavigateOptions {
+                    replace: true,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+}
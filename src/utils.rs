@@ -1,34 +1,161 @@
+#[cfg(target_arch = "wasm32")]
 use leptos::prelude::*;
 
 pub mod local_storage {
+    #[cfg(target_arch = "wasm32")]
     use super::*;
     use thiserror::Error;
 
+    /// An error accessing local storage, carrying the message of the browser's exception
+    /// where there is one, e.g. a `QuotaExceededError` or a `SecurityError` in private mode.
     #[derive(Error, Debug)]
     pub enum LocalStorageError {
-        #[error("local storage is not available")]
-        NotAvailable,
-        #[error("failed to set item in local storage")]
-        SetError,
-        #[error("failed to get item from local storage")]
-        GetError,
+        #[error("local storage is not available: {0}")]
+        NotAvailable(String),
+        #[error("failed to set item in local storage: {0}")]
+        SetError(String),
+        #[error("failed to get item from local storage: {0}")]
+        GetError(String),
+        #[error("failed to remove item from local storage: {0}")]
+        RemoveError(String),
     }
 
-    pub fn set(key: &str, value: &str) -> Result<(), LocalStorageError> {
+    /// Returns the local storage of the window.
+    #[cfg(target_arch = "wasm32")]
+    fn storage() -> Result<web_sys::Storage, LocalStorageError> {
         window()
             .local_storage()
-            .map_err(|_| LocalStorageError::NotAvailable)?
-            .ok_or(LocalStorageError::NotAvailable)?
+            .map_err(|err| LocalStorageError::NotAvailable(js_error(err)))?
+            .ok_or_else(|| LocalStorageError::NotAvailable("no storage on the window".into()))
+    }
+
+    /// Describes an exception thrown by the browser.
+    #[cfg(target_arch = "wasm32")]
+    fn js_error(err: web_sys::wasm_bindgen::JsValue) -> String {
+        err.as_string().unwrap_or_else(|| format!("{err:?}"))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn set(key: &str, value: &str) -> Result<(), LocalStorageError> {
+        storage()?
             .set_item(key, value)
-            .map_err(|_| LocalStorageError::SetError)
+            .map_err(|err| LocalStorageError::SetError(js_error(err)))
     }
 
+    #[cfg(target_arch = "wasm32")]
     pub fn get(key: &str) -> Result<Option<String>, LocalStorageError> {
-        window()
-            .local_storage()
-            .map_err(|_| LocalStorageError::NotAvailable)?
-            .ok_or(LocalStorageError::NotAvailable)?
+        storage()?
             .get_item(key)
-            .map_err(|_| LocalStorageError::GetError)
+            .map_err(|err| LocalStorageError::GetError(js_error(err)))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn remove(key: &str) -> Result<(), LocalStorageError> {
+        storage()?
+            .remove_item(key)
+            .map_err(|err| LocalStorageError::RemoveError(js_error(err)))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    thread_local! {
+        /// The in-memory stand-in for local storage outside of the browser.
+        static STORAGE: std::cell::RefCell<std::collections::HashMap<String, String>> =
+            Default::default();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set(key: &str, value: &str) -> Result<(), LocalStorageError> {
+        STORAGE.with_borrow_mut(|storage| storage.insert(key.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get(key: &str) -> Result<Option<String>, LocalStorageError> {
+        Ok(STORAGE.with_borrow(|storage| storage.get(key).cloned()))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn remove(key: &str) -> Result<(), LocalStorageError> {
+        STORAGE.with_borrow_mut(|storage| storage.remove(key));
+        Ok(())
+    }
+}
+
+/// Language change notifications, sent as custom events on the window in the browser
+/// and through an in-memory list of listeners elsewhere.
+pub mod langid_events {
+    #[cfg(target_arch = "wasm32")]
+    use super::*;
+    use std::sync::Arc;
+
+    /// Notifies every listener of `event_name` that the langid changed to `langid`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn dispatch(event_name: &str, langid: &str) {
+        let custom_event_init = web_sys::CustomEventInit::new();
+        custom_event_init.set_detail(&langid.into());
+        let custom_event =
+            leptos::ev::CustomEvent::new_with_event_init_dict(event_name, &custom_event_init)
+                .expect("should pass always");
+        _ = window().dispatch_event(&custom_event);
+    }
+
+    /// Calls `handler` with the langid of every `event_name` notification,
+    /// until the returned function is called.
+    #[cfg(target_arch = "wasm32")]
+    pub fn listen(
+        event_name: Arc<str>,
+        handler: impl Fn(String) + 'static,
+    ) -> impl FnOnce() + Send + Sync + 'static {
+        let custom_event =
+            leptos::ev::Custom::<leptos::ev::CustomEvent>::new(event_name.to_string());
+        leptos_use::use_event_listener(leptos_use::use_window(), custom_event, move |data| {
+            match data.detail().as_string() {
+                Some(langid) => handler(langid),
+                None => crate::error::report_error(crate::I18nError::LangId(format!(
+                    "invalid data passed in the '{event_name}' event"
+                ))),
+            }
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    type Listener = (usize, Arc<str>, std::rc::Rc<dyn Fn(String)>);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    thread_local! {
+        static LISTENERS: std::cell::RefCell<Vec<Listener>> = Default::default();
+        static NEXT_LISTENER_ID: std::cell::Cell<usize> = Default::default();
+    }
+
+    /// Notifies every listener of `event_name` that the langid changed to `langid`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dispatch(event_name: &str, langid: &str) {
+        // collect the handlers first, so that they can add or remove listeners themselves
+        let handlers: Vec<_> = LISTENERS.with_borrow(|listeners| {
+            listeners
+                .iter()
+                .filter(|(_, name, _)| **name == *event_name)
+                .map(|(_, _, handler)| handler.clone())
+                .collect()
+        });
+        for handler in handlers {
+            handler(langid.to_string());
+        }
+    }
+
+    /// Calls `handler` with the langid of every `event_name` notification,
+    /// until the returned function is called.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn listen(
+        event_name: Arc<str>,
+        handler: impl Fn(String) + 'static,
+    ) -> impl FnOnce() + Send + Sync + 'static {
+        let id = NEXT_LISTENER_ID.with(|next_id| next_id.replace(next_id.get() + 1));
+        LISTENERS.with_borrow_mut(|listeners| {
+            listeners.push((id, event_name, std::rc::Rc::new(handler)));
+        });
+        move || {
+            LISTENERS.with_borrow_mut(|listeners| listeners.retain(|(other, _, _)| *other != id));
+        }
     }
 }
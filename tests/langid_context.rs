@@ -0,0 +1,156 @@
+use i18n_leptos::*;
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn langid(langid: &str) -> i18n::LanguageIdentifier {
+    langid.parse().unwrap()
+}
+
+/// A source recording every langid it stores into `stored`.
+fn recording_source(stored: &Rc<RefCell<Vec<String>>>) -> LangIdSource {
+    let stored = stored.clone();
+    LangIdSource::custom(
+        || None,
+        move |langid| stored.borrow_mut().push(langid.to_string()),
+    )
+}
+
+#[test]
+fn changes_dont_reach_a_disposed_context() {
+    let owner = Owner::new();
+    let stored = Rc::default();
+    let (langid_signal, changer) = owner.with(|| {
+        provide_langid_context(recording_source(&stored), Some(langid("en-US")));
+        (use_langid().unwrap(), use_langid_changer())
+    });
+
+    changer.change(langid("fr-FR"));
+    assert_eq!(langid_signal.get_untracked(), langid("fr-FR"));
+
+    owner.cleanup();
+    changer.change(langid("de-DE"));
+    assert_eq!(langid_signal.get_untracked(), langid("fr-FR"));
+    assert_eq!(*stored.borrow(), ["fr-FR"]);
+}
+
+#[test]
+fn providing_the_context_again_handles_each_change_once() {
+    let owner = Owner::new();
+    let stored = Rc::default();
+    let (langid_signal, changer) = owner.with(|| {
+        provide_langid_context(recording_source(&stored), Some(langid("en-US")));
+        provide_langid_context(recording_source(&stored), Some(langid("en-US")));
+        (use_langid().unwrap(), use_langid_changer())
+    });
+
+    changer.change(langid("fr-FR"));
+    assert_eq!(langid_signal.get_untracked(), langid("fr-FR"));
+    assert_eq!(*stored.borrow(), ["fr-FR"]);
+
+    // a nested context doesn't replace the outer one, which keeps listening once it's disposed
+    let inner = owner.with(|| {
+        let inner = Owner::new();
+        inner.with(|| provide_langid_context(recording_source(&stored), Some(langid("en-US"))));
+        inner
+    });
+    changer.change(langid("de-DE"));
+    assert_eq!(langid_signal.get_untracked(), langid("de-DE"));
+
+    inner.cleanup();
+    changer.change(langid("it-IT"));
+    assert_eq!(langid_signal.get_untracked(), langid("it-IT"));
+    assert_eq!(*stored.borrow(), ["fr-FR", "de-DE", "de-DE", "it-IT"]);
+}
+
+#[test]
+fn remounting_the_context_keeps_listening() {
+    let stored = Rc::default();
+    let first = Owner::new();
+    first.with(|| provide_langid_context(recording_source(&stored), Some(langid("en-US"))));
+    let second = Owner::new();
+    let (langid_signal, changer) = second.with(|| {
+        provide_langid_context(recording_source(&stored), Some(langid("en-US")));
+        (use_langid().unwrap(), use_langid_changer())
+    });
+
+    // unmounting the replaced context must not stop the listener of the new one
+    first.cleanup();
+    changer.change(langid("fr-FR"));
+    assert_eq!(langid_signal.get_untracked(), langid("fr-FR"));
+    assert_eq!(*stored.borrow(), ["fr-FR"]);
+}
+
+#[test]
+fn navigator_contexts_handle_changes_and_resets() {
+    let owner = Owner::new();
+    let (langid_signal, changer) = owner.with(|| {
+        provide_langid_context(LangIdSource::Navigator, Some(langid("de-DE")));
+        (use_langid().unwrap(), use_langid_changer())
+    });
+
+    changer.change(langid("fr-FR"));
+    assert_eq!(langid_signal.get_untracked(), langid("fr-FR"));
+
+    // there is no navigator language outside of the browser
+    changer.reset();
+    assert_eq!(langid_signal.get_untracked(), langid("en-US"));
+}
+
+#[test]
+fn complex_langids_round_trip_through_changes() {
+    let owner = Owner::new();
+    let stored = Rc::default();
+    let (langid_signal, changer) = owner.with(|| {
+        provide_langid_context(recording_source(&stored), Some(langid("en-US")));
+        (use_langid().unwrap(), use_langid_changer())
+    });
+
+    for complex in ["zh-Hant-HK", "de-DE-1996"] {
+        changer.change(langid(complex));
+        assert_eq!(langid_signal.get_untracked(), langid(complex));
+        assert_eq!(langid_signal.get_untracked().to_string(), complex);
+    }
+    assert_eq!(*stored.borrow(), ["zh-Hant-HK", "de-DE-1996"]);
+}
+
+#[test]
+fn available_locales_replace_the_negotiated_langid() {
+    let owner = Owner::new();
+    let langid_signal = owner.with(|| {
+        provide_available_locales([langid("en-US"), langid("zh-Hant")]);
+        provide_langid_context(LangIdSource::custom(|| None, |_| {}), Some(langid("en-US")));
+        use_langid().unwrap()
+    });
+
+    owner.with(|| change_langid(langid("zh-Hant-HK")));
+    assert_eq!(langid_signal.get_untracked(), langid("zh-Hant"));
+}
+
+#[test]
+fn stored_langids_with_extensions_are_rejected() {
+    let owner = Owner::new();
+    let langid_signal = owner.with(|| {
+        provide_langid_context(
+            LangIdSource::custom(|| Some("en-US-u-ca-gregory".to_string()), |_| {}),
+            Some(langid("de-DE")),
+        );
+        use_langid().unwrap()
+    });
+
+    // the extension is not silently dropped into `en-US`
+    assert_eq!(langid_signal.get_untracked(), langid("de-DE"));
+}
+
+#[test]
+fn sibling_default_contexts_each_follow_changes() {
+    let root = Owner::new();
+    let (first, second) = root.with(|| (Owner::new(), Owner::new()));
+    let (first_langid, changer) =
+        first.with(|| (use_langid_or_provide_default(), use_langid_changer()));
+    let second_langid = second.with(use_langid_or_provide_default);
+
+    changer.change(langid("fr-FR"));
+    assert_eq!(first_langid.get_untracked(), langid("fr-FR"));
+    assert_eq!(second_langid.get_untracked(), langid("fr-FR"));
+}
@@ -0,0 +1,28 @@
+use i18n_leptos::*;
+use leptos::prelude::*;
+
+fn message(value: &str) -> i18n::Message {
+    i18n::Message {
+        id: "field".to_string(),
+        value: value.to_string(),
+        attrs: Default::default(),
+    }
+}
+
+#[test]
+fn attributes_can_be_read_within_nested_reads_of_the_message() {
+    let owner = Owner::new();
+    owner.with(|| {
+        let msg = ReactiveMessage::new(RwSignal::new(message("Name")));
+
+        let (placeholder, title) = msg.as_signal().with(|outer| {
+            assert_eq!(outer.value, "Name");
+            let placeholder = msg.attr_or("placeholder", None, "Your name");
+            let title = msg.as_signal().with(|_| msg.attr_or("title", None, "Name"));
+            (placeholder, title)
+        });
+
+        assert_eq!(placeholder, "Your name");
+        assert_eq!(title, "Name");
+    });
+}
@@ -0,0 +1,5 @@
+fn title(msg: i18n_leptos::ReactiveMessage) {
+    let _ = i18n_leptos::rattr!(msg, "title", "default" = "Hi", "default" = "Hello");
+}
+
+fn main() {}
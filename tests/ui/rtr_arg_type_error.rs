@@ -0,0 +1,8 @@
+struct NotFluent;
+
+static LOCALES: std::sync::LazyLock<i18n::Locales> =
+    std::sync::LazyLock::new(i18n::Locales::default);
+
+fn main() {
+    let _ = i18n_leptos::rtr!("greeting", "name" = NotFluent);
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = i18n_leptos::rtr!("items", "plural" = 3, "count" = 4);
+}
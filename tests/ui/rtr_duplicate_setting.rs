@@ -0,0 +1,3 @@
+fn main() {
+    let _ = i18n_leptos::rtr!("greeting", "default" = "Hi", "default" = "Hello");
+}